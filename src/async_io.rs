@@ -0,0 +1,136 @@
+//! Non-blocking counterparts of [`Library::open`] and [`Library::save`], for use on a Tokio
+//! runtime. Files are read and written with `tokio::fs`, and JSON is (de)serialized on the
+//! blocking thread pool.
+
+use std::{io, path::Path};
+
+use chrono::{DateTime, Utc};
+use tokio::{fs, task};
+
+use crate::{Library, SpineError};
+
+impl Library {
+    /// Opens the library from a file like [`Library::open`], without blocking the runtime.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or isn't a valid library.
+    pub async fn open_async(path: impl AsRef<Path>) -> Result<Self, SpineError> {
+        let path = path.as_ref();
+        let contents = fs::read(path).await?;
+        let modified = fs::metadata(path).await?.modified().ok();
+        task::spawn_blocking(move || Self::from_reader(contents.as_slice(), modified))
+            .await
+            .map_err(io::Error::from)?
+            .map(|(library, _)| library)
+    }
+
+    /// Saves the library to a file like [`Library::save`], without blocking the runtime.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`SpineError::Changed`] rather than overwrite a file saved since this library
+    /// was loaded from it; use [`Library::save_forced_async`] to overwrite it anyway.
+    pub async fn save_async(&mut self, path: impl AsRef<Path>) -> Result<(), SpineError> {
+        let path = path.as_ref();
+        if Self::saved_updated_at_async(path).await? > self.updated_at {
+            return Err(SpineError::Changed);
+        }
+        self.save_forced_async(path).await
+    }
+
+    /// Saves the library to a file like [`Library::save_forced`], without blocking the runtime.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be written.
+    pub async fn save_forced_async(&mut self, path: impl AsRef<Path>) -> Result<(), SpineError> {
+        let mut library = self.clone();
+        let path = path.as_ref().to_owned();
+        let saved = task::spawn_blocking(move || {
+            library.save_forced(path)?;
+            Ok::<_, SpineError>((library.created_at, library.updated_at))
+        })
+        .await
+        .map_err(io::Error::from)??;
+        (self.created_at, self.updated_at) = saved;
+
+        Ok(())
+    }
+
+    /// Returns when the library file at `path` was last saved, like `Library::saved_updated_at`.
+    async fn saved_updated_at_async(path: &Path) -> Result<Option<DateTime<Utc>>, SpineError> {
+        let contents = match fs::read(path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        task::spawn_blocking(move || Self::read_updated_at(contents.as_slice()))
+            .await
+            .map_err(io::Error::from)?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Book;
+    use tempfile::tempdir;
+
+    fn library() -> Library {
+        Library::from_books(vec![
+            Book {
+                title: "kim".into(),
+                ..Default::default()
+            },
+            Book {
+                title: "burmese days".into(),
+                ..Default::default()
+            },
+        ])
+    }
+
+    #[tokio::test]
+    async fn save_async_then_open_async_restores_library() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("spine.json");
+        let mut my_lib = library();
+
+        my_lib.save_async(&file_path).await.unwrap();
+        let opened = Library::open_async(&file_path).await.unwrap();
+
+        assert_eq!(opened, my_lib);
+        assert_eq!(Library::open(&file_path).unwrap(), my_lib);
+    }
+
+    #[tokio::test]
+    async fn save_async_refuses_to_overwrite_file_changed_since_open() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("spine.json");
+        library().save(&file_path).unwrap();
+        let mut my_lib = Library::open_async(&file_path).await.unwrap();
+
+        let mut elsewhere = Library::open(&file_path).unwrap();
+        elsewhere.add(Book {
+            title: "animal farm".into(),
+            ..Default::default()
+        });
+        elsewhere.save(&file_path).unwrap();
+        let err = my_lib.save_async(&file_path).await.unwrap_err();
+
+        assert!(matches!(err, SpineError::Changed));
+        my_lib.save_forced_async(&file_path).await.unwrap();
+        assert_eq!(Library::open(&file_path).unwrap().all().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn open_async_fails_for_absent_file() {
+        let tmp_dir = tempdir().unwrap();
+
+        let err = Library::open_async(tmp_dir.path().join("spine.json"))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, SpineError::Io(e) if e.kind() == io::ErrorKind::NotFound));
+    }
+}
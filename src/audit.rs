@@ -0,0 +1,87 @@
+use std::{
+    fs::OpenOptions,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Book, SpineError};
+
+/// A single change to the library, written as one line of the audit log.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub operation: Operation,
+    pub id: Uuid,
+    pub title: String,
+}
+
+impl AuditEntry {
+    #[must_use]
+    pub fn new(operation: Operation, book: &Book) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            operation,
+            id: book.id,
+            title: book.title.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Add,
+    Remove,
+    Update,
+}
+
+/// Appends entries to the audit log as JSON lines, creating the log if needed.
+///
+/// # Errors
+///
+/// Fails if the log can't be opened or written to.
+pub fn append(path: impl AsRef<Path>, entries: &[AuditEntry]) -> Result<(), SpineError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut buf = BufWriter::new(file);
+    for entry in entries {
+        serde_json::to_writer(&mut buf, entry)?;
+        buf.write_all(b"\n")?;
+    }
+    buf.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn append_adds_one_line_per_entry() {
+        let tmp_dir = tempdir().unwrap();
+        let log_path = tmp_dir.path().join("spine.log");
+        let book = Book {
+            title: "kim".to_owned(),
+            ..Default::default()
+        };
+
+        append(&log_path, &[AuditEntry::new(Operation::Add, &book)]).unwrap();
+        append(&log_path, &[AuditEntry::new(Operation::Remove, &book)]).unwrap();
+
+        let contents = fs::read_to_string(&log_path).unwrap();
+        let ops: Vec<_> = contents
+            .lines()
+            .map(|l| serde_json::from_str::<AuditEntry>(l).unwrap().operation)
+            .collect();
+        assert_eq!(ops, vec![Operation::Add, Operation::Remove]);
+    }
+}
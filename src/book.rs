@@ -1,15 +1,26 @@
 use std::{
     collections::HashSet,
     fmt::{self, Display},
-    io,
+    mem,
+    path::PathBuf,
     str::FromStr,
 };
 
-use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 use uuid::Uuid;
 
+use crate::SpineError;
+
+/// Highest rating a book can be given.
+pub const MAX_RATING: u8 = 5;
+
+/// Leading words left out when sorting by title, so "The Hobbit" files under H.
+const IGNORED_ARTICLES: [&str; 3] = ["the ", "a ", "an "];
+
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Book {
+    #[serde(default)]
     pub id: Uuid,
 
     pub title: String,
@@ -20,13 +31,366 @@ pub struct Book {
 
     pub status: Status,
 
-    #[serde(default)]
+    #[serde(default, serialize_with = "serialize_sorted")]
     pub tags: HashSet<String>,
+
+    #[serde(default)]
+    pub date_added: Option<NaiveDate>,
+
+    /// When the book was last moved to reading.
+    #[serde(default)]
+    pub date_started: Option<NaiveDate>,
+
+    #[serde(default)]
+    pub date_finished: Option<NaiveDate>,
+
+    #[serde(default)]
+    pub cover_path: Option<PathBuf>,
+
+    /// Number of times the book has been finished.
+    #[serde(default)]
+    pub times_read: u32,
+
+    #[serde(default)]
+    pub notes: Option<String>,
+
+    /// Archived books are kept but left out of searches unless asked for. Left out of the file
+    /// unless set, so libraries saved before archiving existed don't change.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+
+    /// What the book cost to buy.
+    #[serde(default)]
+    pub price: Option<Money>,
+
+    /// ISO 639-1 code of the language the book is written in, e.g. "en".
+    #[serde(default)]
+    pub language: Option<String>,
+
+    /// Rating out of [`MAX_RATING`].
+    #[serde(default)]
+    pub rating: Option<u8>,
+
+    /// Page reached so far.
+    #[serde(default)]
+    pub page: Option<u32>,
+
+    /// Number of pages in the book.
+    #[serde(default)]
+    pub pages: Option<u32>,
+
+    /// Where the physical copy is kept, e.g. "Living room - shelf 2".
+    #[serde(default)]
+    pub location: Option<String>,
+
+    /// How soon to read the book, 1 being soonest.
+    #[serde(default)]
+    pub priority: Option<u8>,
+}
+
+/// Serializes tags in sorted order, so saved files don't change between runs.
+fn serialize_sorted<S: Serializer>(
+    tags: &HashSet<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut sorted: Vec<&String> = tags.iter().collect();
+    sorted.sort_unstable();
+    sorted.serialize(serializer)
+}
+
+impl Book {
+    /// Returns a builder for a new book.
+    pub fn builder() -> BookBuilder {
+        BookBuilder::default()
+    }
+
+    /// Adds a tag, lowercased and trimmed, returning whether it was added.
+    ///
+    /// Empty tags and tags the book already has are ignored.
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        canonical_tag(tag).is_some_and(|tag| self.tags.insert(tag))
+    }
+
+    /// Removes a tag, ignoring case and surrounding whitespace, returning whether it was present.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        canonical_tag(tag).is_some_and(|tag| self.tags.remove(&tag))
+    }
+
+    /// Trims the text fields, normalises the ISBN and canonicalises the tags, e.g. after the
+    /// book was edited by hand, returning whether anything changed.
+    ///
+    /// An ISBN that isn't valid is left as it is.
+    pub fn normalise(&mut self) -> bool {
+        let before = self.clone();
+        self.title = self.title.trim().to_owned();
+        self.author.first_name = self.author.first_name.trim().to_owned();
+        self.author.surname = self.author.surname.trim().to_owned();
+        if let Some(isbn) = &self.isbn
+            && let Ok(normalised) = Isbn::from_str(isbn.as_str())
+        {
+            self.isbn = Some(normalised);
+        }
+        for tag in mem::take(&mut self.tags) {
+            self.add_tag(&tag);
+        }
+        self.notes = self
+            .notes
+            .take()
+            .map(|n| n.trim().to_owned())
+            .filter(|n| !n.is_empty());
+        *self != before
+    }
+
+    /// Returns the title to sort by: lowercased, without a leading article such as "The".
+    #[must_use]
+    pub fn title_sort_key(&self) -> String {
+        let title = self.title.trim().to_lowercase();
+        IGNORED_ARTICLES
+            .iter()
+            .find_map(|article| title.strip_prefix(article))
+            .map(str::trim_start)
+            .filter(|rest| !rest.is_empty())
+            .map_or_else(|| title.clone(), str::to_owned)
+    }
+
+    /// Returns how far through the book the current page is, as a percentage, if both the page
+    /// and number of pages are known.
+    #[must_use]
+    pub fn progress_percent(&self) -> Option<u8> {
+        let (page, pages) = (self.page?, self.pages.filter(|&p| p > 0)?);
+        let percent = u64::from(page.min(pages)) * 100 / u64::from(pages);
+        u8::try_from(percent).ok()
+    }
+
+    /// Returns the number of days from starting the book to finishing it, if both dates are known
+    /// and it wasn't finished before it was started.
+    #[must_use]
+    pub fn days_to_read(&self) -> Option<i64> {
+        let days = (self.date_finished? - self.date_started?).num_days();
+        (days >= 0).then_some(days)
+    }
+
+    /// Changes the status, starting the book `today` when it becomes reading, finishing it and
+    /// counting another read when it becomes read, and clearing the finish date when it stops
+    /// being read.
+    pub const fn set_status(&mut self, new: Status, today: NaiveDate) {
+        match (self.status, new) {
+            (Status::Read, Status::Read) | (Status::Reading, Status::Reading) => {}
+            (_, Status::Read) => {
+                self.date_finished = Some(today);
+                self.times_read += 1;
+            }
+            (Status::Read, Status::Reading) => {
+                self.date_finished = None;
+                self.date_started = Some(today);
+            }
+            (_, Status::Reading) => self.date_started = Some(today),
+            (Status::Read, _) => self.date_finished = None,
+            _ => {}
+        }
+        self.status = new;
+    }
+
+    /// Checks the title and author are not empty, the ISBN is valid, the rating is at most
+    /// [`MAX_RATING`] and the page is within the book, for books that weren't made with
+    /// [`BookBuilder::build`].
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`SpineError::Validation`] for the first check the book doesn't pass.
+    pub fn validate(&self) -> Result<(), SpineError> {
+        if self.title.trim().is_empty() {
+            return Err(SpineError::Validation(
+                "Invalid title: must not be empty.".into(),
+            ));
+        }
+        Author::from_str(&self.author.to_string())?;
+        if let Some(isbn) = &self.isbn
+            && Isbn::from_str(isbn.as_str())? != *isbn
+        {
+            return Err(SpineError::Validation(format!(
+                "Invalid ISBN: {} is not an {}.",
+                isbn.as_str(),
+                match isbn {
+                    Isbn::Isbn10(_) => "ISBN-10",
+                    Isbn::Isbn13(_) => "ISBN-13",
+                }
+            )));
+        }
+        if self.rating.is_some_and(|r| r == 0 || r > MAX_RATING) {
+            return Err(SpineError::Validation(format!(
+                "Invalid rating: must be from 1 to {MAX_RATING}."
+            )));
+        }
+        if let (Some(page), Some(pages)) = (self.page, self.pages)
+            && page > pages
+        {
+            return Err(SpineError::Validation(format!(
+                "Invalid page: {page} is past the end of a {pages}-page book."
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 impl Display for Book {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}, {}", self.title, self.author)
+        if self.author.first_name.is_empty() {
+            write!(f, "{}", self.title)
+        } else {
+            write!(f, "{}, {}", self.title, self.author)
+        }
+    }
+}
+
+/// A minimal view of a book that stays the same as fields are added to [`Book`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct BookSummary {
+    pub title: String,
+    pub author: String,
+    pub status: Status,
+}
+
+impl From<&Book> for BookSummary {
+    fn from(book: &Book) -> Self {
+        Self {
+            title: book.title.clone(),
+            author: book.author.to_string(),
+            status: book.status,
+        }
+    }
+}
+
+/// Returns the form tags are stored in, or `None` if the tag is empty.
+#[must_use]
+pub fn canonical_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().to_lowercase();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Builds a [`Book`], validating its fields in [`BookBuilder::build`].
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct BookBuilder {
+    title: String,
+    author: String,
+    isbn: Option<String>,
+    status: Status,
+    tags: HashSet<String>,
+    date_added: Option<NaiveDate>,
+    cover_path: Option<PathBuf>,
+    price: Option<Money>,
+    language: Option<String>,
+    location: Option<String>,
+    priority: Option<u8>,
+    notes: Option<String>,
+}
+
+impl BookBuilder {
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.author = author.into();
+        self
+    }
+
+    pub fn isbn(mut self, isbn: impl Into<String>) -> Self {
+        self.isbn = Some(isbn.into());
+        self
+    }
+
+    pub const fn status(mut self, status: Status) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        self.tags
+            .extend(tags.into_iter().filter_map(|t| canonical_tag(&t)));
+        self
+    }
+
+    pub const fn date_added(mut self, date: NaiveDate) -> Self {
+        self.date_added = Some(date);
+        self
+    }
+
+    pub fn cover_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cover_path = Some(path.into());
+        self
+    }
+
+    pub fn price(mut self, price: Money) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    pub fn location(mut self, location: impl Into<String>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    pub const fn priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn notes(mut self, notes: impl Into<String>) -> Self {
+        self.notes = Some(notes.into());
+        self
+    }
+
+    /// Builds the book, checking the title and author are not empty and the ISBN and language
+    /// are valid. A blank location or blank notes are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`SpineError::Validation`] if any of those checks don't pass.
+    pub fn build(self) -> Result<Book, SpineError> {
+        let title = self.title.trim();
+        if title.is_empty() {
+            return Err(SpineError::Validation(
+                "Invalid title: must not be empty.".into(),
+            ));
+        }
+
+        Ok(Book {
+            title: title.to_owned(),
+            author: Author::from_str(&self.author)?,
+            isbn: self.isbn.as_deref().map(Isbn::from_str).transpose()?,
+            status: self.status,
+            tags: self.tags,
+            date_added: self.date_added,
+            cover_path: self.cover_path,
+            price: self.price,
+            language: self.language.as_deref().map(language_code).transpose()?,
+            location: self
+                .location
+                .as_deref()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_owned),
+            priority: self.priority,
+            notes: self
+                .notes
+                .as_deref()
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+                .map(str::to_owned),
+            ..Default::default()
+        })
     }
 }
 
@@ -47,14 +411,13 @@ impl Display for Author {
 }
 
 impl FromStr for Author {
-    type Err = io::Error;
+    type Err = SpineError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut names = s.split_whitespace();
-        let first = names.next().ok_or(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "Invalid author name provided.",
-        ))?;
+        let first = names
+            .next()
+            .ok_or_else(|| SpineError::Validation("Invalid author name provided.".into()))?;
         let surname = names.collect::<Vec<&str>>().join(" ");
 
         Ok(Self {
@@ -71,48 +434,188 @@ pub enum Isbn {
 }
 
 impl Isbn {
+    #[must_use]
     pub fn as_str(&self) -> &str {
         match self {
-            Isbn::Isbn10(s) => s,
-            Isbn::Isbn13(s) => s,
+            Self::Isbn10(s) | Self::Isbn13(s) => s,
         }
     }
+
+    /// Strips the spaces and hyphens from an ISBN-like string, upper-casing any 'x'.
+    #[must_use]
+    pub fn normalise(s: &str) -> String {
+        s.trim().replace([' ', '-'], "").to_uppercase()
+    }
+
+    /// Roughly checks whether a string is meant to be an ISBN: 10 or 13 digits once spaces and
+    /// hyphens are removed, allowing an 'X' check digit.
+    ///
+    /// This catches other arguments passed as an ISBN by mistake, e.g. a title.
+    #[must_use]
+    pub fn looks_like_isbn(s: &str) -> bool {
+        let norm = Self::normalise(s);
+        let body = norm.strip_suffix('X').unwrap_or(&norm);
+        matches!(norm.len(), 10 | 13) && body.chars().all(|c| c.is_ascii_digit())
+    }
 }
 
 impl FromStr for Isbn {
-    type Err = io::Error;
+    type Err = SpineError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let norm = s.replace(" ", "").replace("-", "");
+        let norm = Self::normalise(s);
         let mut rev = norm.chars().rev();
         let last = rev.next();
         for c in rev {
-            if !c.is_digit(10) {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "Invalid ISBN: must contain only digits separated by hyphens or spaces.",
+            if !c.is_ascii_digit() {
+                return Err(SpineError::Validation(
+                    "Invalid ISBN: must contain only digits separated by hyphens or spaces.".into(),
                 ));
             }
         }
         if norm.len() == 10
-            && (last.is_some_and(|c| c.is_digit(10) || c.eq_ignore_ascii_case(&'x')))
+            && (last.is_some_and(|c| c.is_ascii_digit() || c.eq_ignore_ascii_case(&'x')))
         {
-            return Ok(Isbn::Isbn10(norm));
+            return Ok(Self::Isbn10(norm));
         }
         if norm.len() == 13
             && (&norm[..3] == "978" || &norm[..3] == "979")
-            && last.is_some_and(|c| c.is_digit(10))
+            && last.is_some_and(|c| c.is_ascii_digit())
         {
-            return Ok(Isbn::Isbn13(norm));
+            return Ok(Self::Isbn13(norm));
         }
-        Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "Invalid ISBN: must be either 10 digits or 13 digits with a prefix of 978/979.",
+        Err(SpineError::Validation(
+            "Invalid ISBN: must be either 10 digits or 13 digits with a prefix of 978/979.".into(),
         ))
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+/// ISO 639-1 language codes.
+const LANGUAGE_CODES: [&str; 183] = [
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az", "ba", "be", "bg", "bi",
+    "bm", "bn", "bo", "br", "bs", "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy", "da", "de",
+    "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu", "fa", "ff", "fi", "fj", "fo", "fr", "fy",
+    "ga", "gd", "gl", "gn", "gu", "gv", "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz", "ia",
+    "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv", "ka", "kg", "ki", "kj", "kk",
+    "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky", "la", "lb", "lg", "li", "ln", "lo",
+    "lt", "lu", "lv", "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "na", "nb", "nd",
+    "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny", "oc", "oj", "om", "or", "os", "pa", "pi", "pl",
+    "ps", "pt", "qu", "rm", "rn", "ro", "ru", "rw", "sa", "sc", "sd", "se", "sg", "si", "sk", "sl",
+    "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw", "ta", "te", "tg", "th", "ti", "tk",
+    "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty", "ug", "uk", "ur", "uz", "ve", "vi", "vo", "wa",
+    "wo", "xh", "yi", "yo", "za", "zh", "zu",
+];
+
+/// Checks `code` is an ISO 639-1 language code, returning it in lowercase.
+///
+/// # Errors
+///
+/// Fails with [`SpineError::Validation`] if it isn't one.
+pub fn language_code(code: &str) -> Result<String, SpineError> {
+    let code = code.trim().to_lowercase();
+    if LANGUAGE_CODES.binary_search(&code.as_str()).is_ok() {
+        Ok(code)
+    } else {
+        Err(SpineError::Validation(format!(
+            "Invalid language '{code}': expected a two-letter ISO 639-1 code, e.g. 'en' or 'fr'."
+        )))
+    }
+}
+
+/// An amount of money in a currency, e.g. "12.99 GBP".
+///
+/// Amounts are kept in hundredths of the currency's main unit, so sums don't pick up rounding
+/// errors.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Money {
+    amount: u64,
+    currency: String,
+}
+
+impl Money {
+    /// Creates an amount of `amount` hundredths of `currency`, which must be a 3-letter code such
+    /// as "GBP".
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`SpineError::Validation`] if `currency` isn't a 3-letter code.
+    pub fn new(amount: u64, currency: &str) -> Result<Self, SpineError> {
+        let currency = currency.trim();
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(SpineError::Validation(format!(
+                "Invalid currency '{currency}': expected a 3-letter code, e.g. 'GBP'."
+            )));
+        }
+
+        Ok(Self {
+            amount,
+            currency: currency.to_uppercase(),
+        })
+    }
+
+    /// Returns the amount in hundredths of the currency's main unit.
+    #[must_use]
+    pub const fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    #[must_use]
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+}
+
+impl Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}.{:02} {}",
+            self.amount / 100,
+            self.amount % 100,
+            self.currency
+        )
+    }
+}
+
+impl FromStr for Money {
+    type Err = SpineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            SpineError::Validation(format!(
+                "Invalid price '{s}': expected an amount and currency, e.g. '12.99 GBP'."
+            ))
+        };
+        let mut parts = s.split_whitespace();
+        let (Some(amount), Some(currency), None) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty() || !is_digits(whole) || fraction.len() > 2 || !is_digits(fraction) {
+            return Err(invalid());
+        }
+        let cents = format!("{fraction:0<2}")
+            .parse::<u64>()
+            .map_err(|_| invalid())?;
+        let amount = whole
+            .parse::<u64>()
+            .ok()
+            .and_then(|whole| whole.checked_mul(100)?.checked_add(cents))
+            .ok_or_else(invalid)?;
+
+        Self::new(amount, currency)
+    }
+}
+
+/// Reading status of a book, stored on disk as "want", "reading" or "read".
+///
+/// Statuses are ordered by how far along the book is, from want to read. They are read from
+/// disk with [`Status::from_str`], so older files and hand-written ones may use any casing or
+/// synonym it accepts.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Status {
     #[default]
     Want,
@@ -120,26 +623,251 @@ pub enum Status {
     Read,
 }
 
+impl Status {
+    /// Returns the lowercase token used for this status on disk and in exports.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Want => "want",
+            Self::Reading => "reading",
+            Self::Read => "read",
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl FromStr for Status {
-    type Err = io::Error;
+    type Err = SpineError;
 
+    /// Parses "want", "reading" or "read" in any case, or a synonym such as Goodreads' "to-read"
+    /// and "currently-reading". Spaces and underscores count as hyphens.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.trim().to_lowercase().as_str() {
-            "want" => Ok(Status::Want),
-            "reading" => Ok(Status::Reading),
-            "read" => Ok(Status::Read),
-            _ => Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Invalid status: expected 'want', 'reading', or 'read'",
-            )),
+        let token = s.trim().to_lowercase().replace([' ', '_'], "-");
+        match token.as_str() {
+            "want" | "to-read" | "want-to-read" | "tbr" => Ok(Self::Want),
+            "reading" | "currently-reading" | "in-progress" => Ok(Self::Reading),
+            "read" | "finished" => Ok(Self::Read),
+            _ => Err(SpineError::Validation(format!(
+                "Invalid status '{}': expected 'want', 'reading' or 'read'.",
+                s.trim()
+            ))),
         }
     }
 }
 
+impl<'de> Deserialize<'de> for Status {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn language_code_accepts_iso_639_1_code_in_any_case() {
+        assert_eq!(language_code(" FR ").unwrap(), "fr");
+    }
+
+    #[test]
+    fn language_code_rejects_unknown_code() {
+        for code in ["xx", "eng", "french", ""] {
+            let err = language_code(code).unwrap_err();
+
+            assert!(
+                err.to_string().contains("two-letter ISO 639-1 code"),
+                "accepted {code}"
+            );
+        }
+    }
+
+    #[test]
+    fn language_codes_are_sorted_for_binary_search() {
+        assert!(LANGUAGE_CODES.is_sorted());
+    }
+
+    #[test]
+    fn title_sort_key_ignores_leading_article() {
+        for (title, key) in [
+            ("The Hobbit", "hobbit"),
+            ("a Tale of Two Cities", "tale of two cities"),
+            (
+                "AN  Instance of the Fingerpost",
+                "instance of the fingerpost",
+            ),
+            ("Theatre", "theatre"),
+            ("The", "the"),
+        ] {
+            let book = Book {
+                title: title.into(),
+                ..Default::default()
+            };
+
+            assert_eq!(book.title_sort_key(), key, "for {title}");
+        }
+    }
+
+    #[test]
+    fn progress_percent_needs_page_and_pages() {
+        let book = Book {
+            page: Some(45),
+            pages: Some(300),
+            ..Default::default()
+        };
+
+        assert_eq!(book.progress_percent(), Some(15));
+        assert_eq!(
+            Book {
+                pages: None,
+                ..book
+            }
+            .progress_percent(),
+            None
+        );
+    }
+
+    #[test]
+    fn money_parses_amount_and_currency() {
+        let price = Money::from_str("12.5 gbp").unwrap();
+
+        assert_eq!((price.amount(), price.currency()), (1250, "GBP"));
+        assert_eq!(price.to_string(), "12.50 GBP");
+    }
+
+    #[test]
+    fn money_rejects_invalid_currency_code() {
+        for price in ["12.99 POUNDS", "12.99 £", "12.99 G1P"] {
+            let err = Money::from_str(price).unwrap_err();
+
+            assert!(
+                err.to_string().starts_with("Invalid currency"),
+                "accepted {price}"
+            );
+        }
+    }
+
+    #[test]
+    fn builder_builds_book_from_title_and_author() {
+        let book = Book::builder()
+            .title("kim")
+            .author("rudyard kipling")
+            .build()
+            .unwrap();
+
+        assert_eq!(book.title, "kim");
+        assert_eq!(book.author.to_string(), "rudyard kipling");
+        assert_eq!(book.isbn, None);
+        assert_eq!(book.status, Status::Want);
+    }
+
+    #[test]
+    fn builder_throws_error_if_title_empty() {
+        let err = Book::builder()
+            .title("  ")
+            .author("rudyard kipling")
+            .build()
+            .unwrap_err();
+
+        assert!(err.to_string().contains("Invalid title"));
+    }
+
+    #[test]
+    fn builder_throws_error_if_isbn_invalid() {
+        let err = Book::builder()
+            .title("kim")
+            .author("rudyard kipling")
+            .isbn("12345")
+            .build()
+            .unwrap_err();
+
+        assert!(err.to_string().contains("Invalid ISBN"));
+    }
+
+    #[test]
+    fn validate_throws_error_if_isbn_invalid() {
+        let book = Book {
+            title: "kim".to_owned(),
+            author: Author::from_str("rudyard kipling").unwrap(),
+            isbn: Some(Isbn::Isbn13("kim".to_owned())),
+            ..Default::default()
+        };
+
+        let err = book.validate().unwrap_err();
+
+        assert!(err.to_string().contains("Invalid ISBN"));
+    }
+
+    #[test]
+    fn builder_dedupes_tags_and_drops_empty_ones() {
+        let book = Book::builder()
+            .title("dune")
+            .author("frank herbert")
+            .tags(["Sci-Fi".into(), " sci-fi ".into(), String::new()])
+            .build()
+            .unwrap();
+
+        assert_eq!(book.tags, HashSet::from(["sci-fi".to_owned()]));
+    }
+
+    #[test]
+    fn add_tag_ignores_empty_and_duplicate_tags() {
+        let mut book = Book::default();
+
+        assert!(book.add_tag("Classic"));
+        assert!(!book.add_tag("classic "));
+        assert!(!book.add_tag("  "));
+        assert_eq!(book.tags, HashSet::from(["classic".to_owned()]));
+    }
+
+    #[test]
+    fn remove_tag_removes_present_tag() {
+        let mut book = Book::default();
+        book.add_tag("classic");
+
+        assert!(book.remove_tag(" CLASSIC"));
+        assert!(book.tags.is_empty());
+    }
+
+    #[test]
+    fn remove_tag_ignores_absent_tag() {
+        let mut book = Book::default();
+        book.add_tag("classic");
+
+        assert!(!book.remove_tag("russian"));
+        assert_eq!(book.tags.len(), 1);
+    }
+
+    #[test]
+    fn summary_keeps_title_author_and_status() {
+        let book = Book::builder()
+            .title("kim")
+            .author("rudyard kipling")
+            .isbn("9780199536467")
+            .status(Status::Read)
+            .tags(["classic".into()])
+            .build()
+            .unwrap();
+
+        let summary = BookSummary::from(&book);
+
+        assert_eq!(
+            summary,
+            BookSummary {
+                title: "kim".into(),
+                author: "rudyard kipling".into(),
+                status: Status::Read,
+            }
+        );
+    }
+
     #[test]
     fn author_creates_from_three_names() {
         let author = Author::from_str("ursula le guin").unwrap();
@@ -165,6 +893,20 @@ mod tests {
         assert!(err.to_string().contains("Invalid author name"));
     }
 
+    #[test]
+    fn looks_like_isbn_accepts_hyphenated_digits_and_check_x() {
+        assert!(Isbn::looks_like_isbn("978-0-19-953646-7"));
+        assert!(Isbn::looks_like_isbn("1-23456789-x"));
+    }
+
+    #[test]
+    fn looks_like_isbn_rejects_titles_and_wrong_lengths() {
+        assert!(!Isbn::looks_like_isbn("kim"));
+        assert!(!Isbn::looks_like_isbn("animal farm"));
+        assert!(!Isbn::looks_like_isbn("12345"));
+        assert!(!Isbn::looks_like_isbn("12345X789X"));
+    }
+
     #[test]
     fn isbn_creates_from_10_digits_ending_x() {
         let isbn = Isbn::from_str("1-23456789-X").unwrap();
@@ -185,4 +927,149 @@ mod tests {
 
         assert!(err.to_string().contains("Invalid ISBN"));
     }
+
+    #[test]
+    fn set_status_want_to_read_finishes_and_counts_read() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        let mut book = Book::default();
+
+        book.set_status(Status::Read, today);
+
+        assert_eq!(book.status, Status::Read);
+        assert_eq!(book.date_finished, Some(today));
+        assert_eq!(book.times_read, 1);
+    }
+
+    #[test]
+    fn set_status_reading_starts_book_and_keeps_start_once_read() {
+        let started = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let finished = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        let mut book = Book::default();
+
+        book.set_status(Status::Reading, started);
+        book.set_status(Status::Reading, finished);
+        book.set_status(Status::Read, finished);
+
+        assert_eq!(book.date_started, Some(started));
+        assert_eq!(book.date_finished, Some(finished));
+    }
+
+    #[test]
+    fn days_to_read_counts_days_from_start_to_finish() {
+        let book = Book {
+            date_started: NaiveDate::from_ymd_opt(2024, 2, 20),
+            date_finished: NaiveDate::from_ymd_opt(2024, 3, 9),
+            ..Default::default()
+        };
+
+        assert_eq!(book.days_to_read(), Some(18));
+    }
+
+    #[test]
+    fn days_to_read_is_none_without_both_dates_or_if_finished_first() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d);
+        for (started, finished) in [(day(1), None), (None, day(9)), (day(9), day(1))] {
+            let book = Book {
+                date_started: started,
+                date_finished: finished,
+                ..Default::default()
+            };
+
+            assert_eq!(book.days_to_read(), None, "{started:?} to {finished:?}");
+        }
+    }
+
+    #[test]
+    fn set_status_read_to_want_clears_finish_date() {
+        let mut book = Book {
+            status: Status::Read,
+            date_finished: NaiveDate::from_ymd_opt(2023, 1, 1),
+            times_read: 1,
+            ..Default::default()
+        };
+
+        book.set_status(Status::Want, NaiveDate::from_ymd_opt(2024, 3, 9).unwrap());
+
+        assert_eq!(book.status, Status::Want);
+        assert_eq!(book.date_finished, None);
+        assert_eq!(book.times_read, 1);
+    }
+
+    #[test]
+    fn status_serializes_to_stable_lowercase_tokens() {
+        let tokens = [Status::Want, Status::Reading, Status::Read]
+            .map(|s| serde_json::to_string(&s).unwrap());
+
+        assert_eq!(tokens, [r#""want""#, r#""reading""#, r#""read""#]);
+    }
+
+    #[test]
+    fn archived_is_serialized_only_when_set() {
+        let mut book = Book {
+            title: "kim".to_owned(),
+            ..Default::default()
+        };
+
+        let unarchived = serde_json::to_string(&book).unwrap();
+        book.archived = true;
+        let archived = serde_json::to_string(&book).unwrap();
+
+        assert!(!unarchived.contains("archived"), "{unarchived}");
+        assert!(archived.contains(r#""archived":true"#), "{archived}");
+    }
+
+    #[test]
+    fn status_deserializes_from_legacy_variant_names() {
+        let status: Status = serde_json::from_str(r#""Reading""#).unwrap();
+
+        assert_eq!(status, Status::Reading);
+    }
+
+    #[test]
+    fn status_parses_tokens_in_any_case() {
+        let statuses = [" want", "READING", "Read "].map(|s| Status::from_str(s).unwrap());
+
+        assert_eq!(statuses, [Status::Want, Status::Reading, Status::Read]);
+    }
+
+    #[test]
+    fn status_parses_synonyms() {
+        for (synonym, expected) in [
+            ("to-read", Status::Want),
+            ("Want to read", Status::Want),
+            ("currently_reading", Status::Reading),
+            ("finished", Status::Read),
+        ] {
+            assert_eq!(Status::from_str(synonym).unwrap(), expected, "{synonym}");
+        }
+    }
+
+    #[test]
+    fn status_rejects_unknown_value() {
+        for value in ["abandoned", "", "reads"] {
+            let err = Status::from_str(value).unwrap_err();
+
+            assert!(matches!(err, SpineError::Validation(_)), "{value}");
+            assert!(
+                err.to_string()
+                    .contains("expected 'want', 'reading' or 'read'")
+            );
+        }
+    }
+
+    #[test]
+    fn status_display_parses_back_to_same_status() {
+        for status in [Status::Want, Status::Reading, Status::Read] {
+            assert_eq!(Status::from_str(&status.to_string()).unwrap(), status);
+        }
+    }
+
+    #[test]
+    fn status_deserializes_from_synonym_and_rejects_unknown_value() {
+        let status: Status = serde_json::from_str(r#""to-read""#).unwrap();
+        let err = serde_json::from_str::<Status>(r#""abandoned""#).unwrap_err();
+
+        assert_eq!(status, Status::Want);
+        assert!(err.to_string().contains("Invalid status 'abandoned'"));
+    }
 }
@@ -1,22 +1,254 @@
 use std::{
     ffi::OsString,
-    io::{self, BufRead},
+    fmt::{Display, Write as _},
+    fs::{self, File},
+    io::{self, BufRead, IsTerminal, Read, Write},
+    iter, mem,
     num::ParseIntError,
-    path::Path,
-    str::FromStr,
+    path::{Path, PathBuf},
+    slice,
+    time::Duration,
 };
 
-use anyhow::bail;
-use clap::{Args, CommandFactory, Parser, Subcommand};
+use anyhow::Context;
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
+use clap::{
+    Args, CommandFactory, FromArgMatches, Parser, Subcommand,
+    builder::{OsStringValueParser, TypedValueParser},
+    parser::ValueSource,
+};
+use ratatui::crossterm::style::Stylize;
 use uuid::Uuid;
 
-use crate::{Author, Book, Isbn, Library, LibrarySearch, Status};
+use crate::{
+    Author, Book, BookUpdate, ConflictPolicy, Isbn, Library, LibrarySearch, Money, SpineError,
+    Status,
+    audit::{self, AuditEntry, Operation},
+    book::{MAX_RATING, canonical_tag, language_code},
+    clock::{Clock, SystemClock},
+    config::{ColorChoice, Config, DuplicatePolicy},
+    export::{self, ExportFormat},
+    ids::IdGenerator,
+    import::{self, CsvMapping},
+    lock::LibraryLock,
+    resolve_single,
+    share::SharedBook,
+    sort::GroupSort,
+    stats::{self, LibraryStats, Period, Report},
+    verify,
+};
+
+/// Format for showing dates when `--date-format` isn't set or is invalid.
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Profile used when `--profile` isn't given, stored as `spine.json`.
+const DEFAULT_PROFILE: &str = "spine";
+
+/// How long to wait for another spine process to finish with the library.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[command(flatten)]
+    save: SaveArgs,
+
+    /// Don't clean up stray whitespace, ISBN formatting and duplicate tags when loading
+    #[arg(long, global = true)]
+    no_normalise: bool,
+
+    /// Append a JSON line to this file for every change made
+    #[arg(long, global = true, env = "SPINE_LOG")]
+    log: Option<PathBuf>,
+
+    /// strftime-style format for showing dates, e.g. "%d/%m/%Y"
+    #[arg(long, global = true, env = "SPINE_DATE_FORMAT")]
+    date_format: Option<String>,
+
+    /// Whether to highlight output: auto, always or never. Auto highlights unless `NO_COLOR` is set
+    #[arg(long, global = true, value_name = "WHEN")]
+    color: Option<ColorChoice>,
+
+    #[command(flatten)]
+    library: LibraryArgs,
+
+    /// Don't print informational messages, only errors and requested output
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Give new books ids from a fixed sequence for this seed instead of random ids, for tests
+    #[arg(long, global = true, env = "SPINE_UUID_SEED", hide = true)]
+    uuid_seed: Option<u64>,
+}
+
+/// Options of the TUI, which spine runs when started without `--cli`.
+#[derive(Parser)]
+#[command(version, about, long_about = None)]
+pub struct TuiArgs {
+    #[command(flatten)]
+    library: LibraryArgs,
+
+    /// Draw a border around the list of books
+    #[arg(long)]
+    pub border: bool,
+
+    /// Leave out the border around the list of books, fitting in more of them
+    #[arg(long)]
+    pub no_border: bool,
+}
+
+impl TuiArgs {
+    /// Returns the path of the library to show, loading the config file for its data directory.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the config file can't be read or isn't valid.
+    pub fn library_path(&self) -> anyhow::Result<PathBuf> {
+        let config = self.library.load_config()?;
+        Ok(self.library.library_path(&config))
+    }
+}
+
+/// Options choosing the library file, shared by the command line and the TUI.
+#[derive(Args)]
+struct LibraryArgs {
+    /// Read default options from this file, instead of spine.toml in the config directory
+    #[arg(long, global = true, env = "SPINE_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Library to use, stored as `<PROFILE>.json` in the data directory
+    #[arg(
+        long,
+        global = true,
+        default_value = DEFAULT_PROFILE,
+        value_parser = OsStringValueParser::new().try_map(parse_profile),
+    )]
+    profile: OsString,
+
+    /// Directory libraries are stored in, instead of the current directory
+    #[arg(long, global = true, env = "SPINE_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+}
+
+impl LibraryArgs {
+    /// Loads the config file given with `--config`, or else the one in the config directory if
+    /// there is one.
+    fn load_config(&self) -> anyhow::Result<Config> {
+        match (&self.config, Config::default_path()) {
+            (Some(path), _) => load_config(path, true),
+            (None, Some(path)) => load_config(&path, false),
+            (None, None) => Ok(Config::default()),
+        }
+    }
+
+    /// Returns the path of the profile's library, in the data directory given with
+    /// `--data-dir`, or else by `config`, or else the current directory.
+    fn library_path(&self, config: &Config) -> PathBuf {
+        let data_dir = self
+            .data_dir
+            .clone()
+            .or_else(|| config.data_dir.clone())
+            .unwrap_or_default();
+        let mut file_name = self.profile.clone();
+        file_name.push(".json");
+        data_dir.join(file_name)
+    }
+}
+
+#[derive(Args)]
+struct SaveArgs {
+    /// Apply changes in memory without saving them to disk
+    #[arg(long, global = true)]
+    no_save: bool,
+
+    /// Save even if the library file changed since it was loaded, overwriting those changes
+    #[arg(long, global = true, conflicts_with = "no_save")]
+    force: bool,
+}
+
+/// How changes are written back to the library file.
+#[derive(Clone, Copy)]
+enum SaveMode {
+    /// Save, unless the file changed since it was loaded.
+    Checked,
+    /// Save, overwriting any changes made to the file since it was loaded.
+    Forced,
+    /// Don't save.
+    Skipped,
+}
+
+impl From<SaveArgs> for SaveMode {
+    fn from(args: SaveArgs) -> Self {
+        if args.no_save {
+            Self::Skipped
+        } else if args.force {
+            Self::Forced
+        } else {
+            Self::Checked
+        }
+    }
+}
+
+/// State shared by the commands run in a single invocation.
+struct Session {
+    path: PathBuf,
+    save_mode: SaveMode,
+    defer_save: bool,
+    log: Option<PathBuf>,
+    changes: Vec<AuditEntry>,
+    date_format: String,
+    use_color: bool,
+    quiet: bool,
+    clock: Box<dyn Clock>,
+    /// Defaults from the config file for options of individual commands.
+    config: Config,
+    /// Lock on the library, held by commands that change it.
+    lock: Option<LibraryLock>,
+}
+
+impl Session {
+    /// Prints an informational message, unless `--quiet` is set.
+    fn say(&self, message: impl Display) {
+        if !self.quiet {
+            println!("{message}");
+        }
+    }
+
+    fn record(&mut self, operation: Operation, book: &Book) {
+        self.changes.push(AuditEntry::new(operation, book));
+    }
+
+    /// Saves the library, then appends the recorded changes to the audit log.
+    ///
+    /// Takes the lock on the library again if it was released, e.g. while asking a question.
+    /// Failing to write the audit log only prints a warning.
+    fn save(&mut self, lib: &mut Library) -> anyhow::Result<()> {
+        if self.defer_save {
+            return Ok(());
+        }
+        if self.lock.is_none() && !matches!(self.save_mode, SaveMode::Skipped) {
+            self.lock = Some(LibraryLock::acquire(&self.path, LOCK_TIMEOUT)?);
+        }
+        match self.save_mode {
+            SaveMode::Checked => lib.save(&self.path)?,
+            SaveMode::Forced => lib.save_forced(&self.path)?,
+            SaveMode::Skipped => {
+                self.changes.clear();
+                return Ok(());
+            }
+        }
+        if let Some(log) = &self.log
+            && let Err(e) = audit::append(log, &self.changes)
+        {
+            eprintln!("Warning: could not write to audit log: {e}");
+        }
+        self.changes.clear();
+
+        Ok(())
+    }
 }
 
 #[derive(Subcommand)]
@@ -24,22 +256,146 @@ enum Commands {
     /// Show your books
     Show(ShowArgs),
 
+    /// Search your books, highlighting what matched
+    Search(SearchCommandArgs),
+
     /// Add a new book
     Add(AddArgs),
 
     /// Remove an existing book
-    Remove(SearchArgs),
+    Remove(RemoveArgs),
 
     /// Update an existing book
     #[command(subcommand)]
     Update(UpdateType),
+
+    /// Add or remove a tag on existing books
+    #[command(subcommand)]
+    Tag(TagAction),
+
+    /// Add a copy of an existing book, e.g. another edition, as a new want-to-read book
+    Clone(CloneArgs),
+
+    /// Print a snippet of a book to send to someone, who can add it with `add --from`
+    Share(SearchArgs),
+
+    /// Show the book to read next: the want-to-read book with the highest priority, then the
+    /// oldest
+    Peek {
+        /// Show every detail stored for the book
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Export your books to another format
+    Export(ExportArgs),
+
+    /// Merge the books from another spine library file into yours
+    Import(ImportArgs),
+
+    /// Compare your library with another spine library file
+    Diff {
+        /// Library file to compare with
+        file: PathBuf,
+    },
+
+    /// Show progress towards your yearly reading goal
+    Goal {
+        /// Set the number of books to finish each year
+        #[arg(long)]
+        set: Option<u32>,
+    },
+
+    /// Show counts of your books by status and by year finished
+    Stats {
+        /// Print the stats as JSON
+        #[arg(long, conflicts_with = "csv")]
+        json: bool,
+
+        /// Print the books finished each year as CSV
+        #[arg(long)]
+        csv: bool,
+
+        /// Also show the size of the library file
+        #[arg(short, long, conflicts_with_all = ["json", "csv"])]
+        verbose: bool,
+
+        /// Show how much you spent on books, totalled for each currency
+        #[arg(long, conflicts_with_all = ["json", "csv", "verbose"])]
+        spent: bool,
+
+        /// Show which books are kept in each location
+        #[arg(long, conflicts_with_all = ["json", "csv", "verbose", "spent"])]
+        by_location: bool,
+    },
+
+    /// Show the books finished, started and added this week or month
+    Report {
+        /// One of week or month
+        #[arg(long, default_value = "month")]
+        period: Period,
+    },
+
+    /// List the profiles in the data directory, one for each library file there
+    Profiles,
+
+    /// Show when your library was created and last saved
+    Info,
+
+    /// Check the library file for invalid JSON, missing or duplicate ids and invalid books,
+    /// failing if any are found
+    Verify,
+
+    /// Run commands from a file, one per line, saving once at the end
+    Batch {
+        /// File with one command per line, e.g. `add "kim" "rudyard kipling" --read`
+        file: PathBuf,
+    },
+}
+
+impl Commands {
+    /// Returns whether the command may change the library, so must lock it while it runs.
+    const fn modifies_library(&self) -> bool {
+        !matches!(
+            self,
+            Self::Show(_)
+                | Self::Search(_)
+                | Self::Share(_)
+                | Self::Peek { .. }
+                | Self::Export(_)
+                | Self::Diff { .. }
+                | Self::Goal { set: None }
+                | Self::Stats { .. }
+                | Self::Report { .. }
+                | Self::Profiles
+                | Self::Info
+                | Self::Verify
+        )
+    }
 }
 
 #[derive(Args)]
 struct AddArgs {
-    title: String,
+    #[arg(required_unless_present_any = ["stdin", "from"])]
+    title: Option<String>,
+
+    #[arg(required_unless_present_any = ["stdin", "from"])]
+    author: Option<String>,
 
-    author: String,
+    /// Read a JSON book, or an array of books, from standard input instead, with all its
+    /// details
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "title", "author", "isbn", "StatusFlag", "tags", "cover", "price", "lang", "location",
+            "notes", "priority", "normalize_author", "duplicates",
+        ]
+    )]
+    stdin: bool,
+
+    /// Add the book in a snippet printed by `spine share`, instead of giving its details
+    #[arg(long, conflicts_with_all = ["title", "author", "stdin", "isbn"])]
+    from: Option<SharedBook>,
 
     #[arg(short, long)]
     isbn: Option<String>,
@@ -49,6 +405,86 @@ struct AddArgs {
 
     #[arg(long, alias = "tag", value_delimiter = ',')]
     tags: Vec<String>,
+
+    /// Path to a cover image of the book
+    #[arg(long)]
+    cover: Option<PathBuf>,
+
+    /// What the book cost, as an amount and 3-letter currency code, e.g. "12.99 GBP"
+    #[arg(long)]
+    price: Option<Money>,
+
+    /// Two-letter ISO 639-1 code of the language the book is written in, e.g. "en"
+    #[arg(long, value_parser = language_code)]
+    lang: Option<String>,
+
+    /// Where the physical copy is kept, e.g. "Living room - shelf 2"
+    #[arg(long)]
+    location: Option<String>,
+
+    /// Anything else to remember about the book, e.g. who recommended it
+    #[arg(long)]
+    notes: Option<String>,
+
+    /// How soon to read the book, 1 being soonest, for ordering `show --group`
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..))]
+    priority: Option<u8>,
+
+    /// Use the casing of a matching author already in the library
+    #[arg(long)]
+    normalize_author: bool,
+
+    /// Status to use when no status flag is given, instead of want
+    #[arg(long, env = "SPINE_DEFAULT_STATUS")]
+    default_status: Option<Status>,
+
+    /// What to do if the book is already in the library: allow, warn or reject
+    #[arg(long, value_name = "POLICY")]
+    duplicates: Option<DuplicatePolicy>,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    /// One of csv, markdown, json, bibtex or timeline
+    #[arg(short, long)]
+    format: ExportFormat,
+
+    /// File to write to, instead of standard output
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Keep the order books were added in, instead of sorting by author and title
+    #[arg(long)]
+    preserve_order: bool,
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    /// Library file to merge in
+    #[arg(required_unless_present_any = ["titles", "csv"])]
+    file: Option<PathBuf>,
+
+    /// Add a want-to-read book for each line of this file, as `title` or `title|author`
+    #[arg(long, conflicts_with_all = ["file", "on_conflict", "csv"])]
+    titles: Option<PathBuf>,
+
+    /// Add a book for each row of this CSV file, such as one from `export --format csv`, finding
+    /// each field's column by its name in the header unless --map is given
+    #[arg(long, conflicts_with_all = ["file", "on_conflict"])]
+    csv: Option<PathBuf>,
+
+    /// 1-based CSV columns of each field, e.g. `title=1,author=2,isbn=3,status=4`. Tags,
+    /// `date_added` and `date_finished` may be mapped too
+    #[arg(long, requires = "csv")]
+    map: Option<CsvMapping>,
+
+    /// Read the first row of the CSV file as a book rather than a header
+    #[arg(long, requires = "map")]
+    no_header: bool,
+
+    /// For books in both with different statuses: keep, incoming, furthest or ask
+    #[arg(long, default_value = "furthest")]
+    on_conflict: ConflictPolicy,
 }
 
 #[derive(Args)]
@@ -56,11 +492,68 @@ struct ShowArgs {
     #[arg(long)]
     all: bool,
 
+    /// Show every detail stored for each book
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Print only the number of matching books
+    #[arg(long, conflicts_with_all = ["verbose", "recent", "group"])]
+    count: bool,
+
+    #[command(flatten)]
+    layout: LayoutArgs,
+
+    /// Match this anywhere in the title, author, notes or tags
+    #[arg(value_name = "QUERY")]
+    query: Option<String>,
+
+    #[command(flatten)]
+    search: SearchArgs,
+}
+
+#[derive(Args)]
+struct SearchCommandArgs {
+    /// Print only the number of matching books
+    #[arg(long)]
+    count: bool,
+
+    /// Match titles against this regular expression instead, ignoring case unless it starts
+    /// with (?-i)
+    #[cfg(feature = "regex")]
+    #[arg(long, conflicts_with_all = ["SearchArgs", "query"])]
+    regex: Option<String>,
+
+    /// Also match the --regex pattern against authors
+    #[cfg(feature = "regex")]
+    #[arg(long, requires = "regex")]
+    regex_author: bool,
+
+    /// Match this anywhere in the title, author, notes or tags
+    #[arg(value_name = "QUERY")]
+    query: Option<String>,
+
     #[command(flatten)]
     search: SearchArgs,
 }
 
 #[derive(Args)]
+struct LayoutArgs {
+    /// List the most recently added books first
+    #[arg(long)]
+    recent: bool,
+
+    /// List your books, or those matching the search, under a heading for each status
+    #[arg(long, conflicts_with = "recent")]
+    group: bool,
+
+    /// Order of the books under each heading with --group, e.g. `read=title,want=added-desc`.
+    /// Sorts by added, started, finished, priority, title or author, ending in -desc to reverse.
+    /// Defaults to `want=priority,reading=started,read=finished-desc`
+    #[arg(long, env = "SPINE_GROUP_SORT", value_name = "ORDER")]
+    group_sort: Option<GroupSort>,
+}
+
+#[derive(Args, Default)]
 #[group(required = false, multiple = true)]
 struct SearchArgs {
     #[arg(short, long)]
@@ -72,33 +565,191 @@ struct SearchArgs {
     #[arg(short, long)]
     isbn: Option<String>,
 
-    #[arg(short, long)]
-    status: Option<String>,
+    /// Match the ISBN by its last digits, e.g. when a scanner truncated it
+    #[arg(long, requires = "isbn")]
+    isbn_suffix: bool,
+
+    /// Match any of these statuses, e.g. `--status want --status reading`
+    #[arg(short, long, value_delimiter = ',')]
+    status: Option<Vec<Status>>,
 
     #[arg(long, alias = "tag", value_delimiter = ',')]
     tags: Option<Vec<String>>,
+
+    /// Set from the positional query of `show` and `search`
+    #[arg(skip)]
+    text: Option<String>,
+
+    /// Match books added on or after this date, e.g. 2024-01-31
+    #[arg(long, value_name = "DATE")]
+    added_since: Option<NaiveDate>,
+
+    /// Match books added on or before this date
+    #[arg(long, value_name = "DATE")]
+    added_until: Option<NaiveDate>,
+
+    /// Match books finished on or after this date
+    #[arg(long, value_name = "DATE")]
+    finished_since: Option<NaiveDate>,
+
+    /// Match books finished on or before this date
+    #[arg(long, value_name = "DATE")]
+    finished_until: Option<NaiveDate>,
+
+    /// Match books in this language, as a two-letter ISO 639-1 code
+    #[arg(long, value_parser = language_code)]
+    lang: Option<String>,
+
+    /// Match books whose location contains this
+    #[arg(long)]
+    location: Option<String>,
+
+    /// Match archived books instead of the rest
+    #[arg(long)]
+    archived: bool,
 }
 
 impl SearchArgs {
-    fn is_any_set(&self) -> bool {
+    const fn is_any_set(&self) -> bool {
         self.title.is_some()
             || self.author.is_some()
             || self.isbn.is_some()
             || self.status.is_some()
             || self.tags.is_some()
+            || self.text.is_some()
+            || self.added_since.is_some()
+            || self.added_until.is_some()
+            || self.finished_since.is_some()
+            || self.finished_until.is_some()
+            || self.lang.is_some()
+            || self.location.is_some()
+            || self.archived
+    }
+}
+
+#[derive(Args)]
+struct RemoveArgs {
+    /// Archive the book instead, keeping it out of searches without deleting it
+    #[arg(long)]
+    archive: bool,
+
+    #[command(flatten)]
+    pick: PickArgs,
+
+    #[command(flatten)]
+    search: SearchArgs,
+}
+
+#[derive(Args)]
+struct PickArgs {
+    /// If several books match, act on the first added instead of asking which
+    #[arg(long, conflicts_with = "last")]
+    first: bool,
+
+    /// If several books match, act on the last added instead of asking which
+    #[arg(long)]
+    last: bool,
+}
+
+impl PickArgs {
+    /// Returns the book `--first` or `--last` picks from `hits`, if either was passed.
+    const fn pick<'a>(&self, hits: &[&'a Book]) -> Option<&'a Book> {
+        if self.first {
+            hits.first().copied()
+        } else if self.last {
+            hits.last().copied()
+        } else {
+            None
+        }
     }
 }
 
 #[derive(Subcommand)]
 enum UpdateType {
     /// Update the status of an existing book
-    Status {
-        #[command(flatten)]
-        status: StatusFlag,
+    Status(UpdateStatusArgs),
 
-        #[command(flatten)]
-        search: SearchArgs,
-    },
+    /// Update the notes of an existing book
+    Notes(UpdateNotesArgs),
+
+    /// Move an archived book back into your library
+    Unarchive(SearchArgs),
+}
+
+#[derive(Subcommand)]
+enum TagAction {
+    /// Add a tag to matching books
+    Add(TagArgs),
+
+    /// Remove a tag from matching books
+    Remove(TagArgs),
+}
+
+#[derive(Args)]
+struct TagArgs {
+    /// Tag to add or remove
+    tag: String,
+
+    /// Change every matching book, instead of asking which
+    #[arg(long, conflicts_with_all = ["first", "last"])]
+    all: bool,
+
+    #[command(flatten)]
+    pick: PickArgs,
+
+    #[command(flatten)]
+    search: SearchArgs,
+}
+
+#[derive(Args)]
+struct CloneArgs {
+    /// Title for the copy, instead of the original's
+    #[arg(long)]
+    new_title: Option<String>,
+
+    /// Author for the copy, instead of the original's
+    #[arg(long)]
+    new_author: Option<Author>,
+
+    /// ISBN for the copy, instead of the original's
+    #[arg(long)]
+    new_isbn: Option<Isbn>,
+
+    #[command(flatten)]
+    search: SearchArgs,
+}
+
+#[derive(Args)]
+struct UpdateStatusArgs {
+    #[command(flatten)]
+    status: StatusFlag,
+
+    /// Count another read of the book, even if it is already read
+    #[arg(long, requires = "read")]
+    reread: bool,
+
+    /// Update every matching book, instead of asking which
+    #[arg(long, conflicts_with_all = ["first", "last"])]
+    all: bool,
+
+    #[command(flatten)]
+    pick: PickArgs,
+
+    #[command(flatten)]
+    search: SearchArgs,
+}
+
+#[derive(Args)]
+struct UpdateNotesArgs {
+    /// New notes for the book, replacing any it has. Empty notes clear them
+    #[arg(long)]
+    notes: String,
+
+    #[command(flatten)]
+    pick: PickArgs,
+
+    #[command(flatten)]
+    search: SearchArgs,
 }
 
 #[derive(Args)]
@@ -115,176 +766,1422 @@ struct StatusFlag {
 }
 
 impl StatusFlag {
-    fn to_status(&self) -> Status {
-        match (self.reading, self.read) {
-            (true, _) => Status::Reading,
-            (_, true) => Status::Read,
-            _ => Status::Want,
+    /// Returns the status of the flag that was passed, or `default` if none was.
+    const fn to_status(&self, default: Status) -> Status {
+        match (self.want, self.reading, self.read) {
+            (true, _, _) => Status::Want,
+            (_, true, _) => Status::Reading,
+            (_, _, true) => Status::Read,
+            _ => default,
         }
     }
 
-    fn is_set(&self) -> bool {
+    const fn is_set(&self) -> bool {
         self.want || self.reading || self.read
     }
 }
 
+/// Runs the command given by the command-line `args`.
+///
+/// # Errors
+///
+/// Fails if the library can't be loaded or saved, or the command fails.
 pub fn main<I, T>(args: I) -> anyhow::Result<()>
 where
     I: IntoIterator<Item = T>,
     T: Into<OsString> + Clone,
 {
     let cli = Cli::parse_from(args);
-
-    let path = Path::new("spine.json");
-    let mut my_lib = if path.exists() {
-        Library::open(path)?
+    let config = cli.library.load_config()?;
+    let path = cli.library.library_path(&config);
+    let modifies_library = cli.command.modifies_library();
+    let lock = if modifies_library {
+        Some(LibraryLock::acquire(&path, LOCK_TIMEOUT)?)
     } else {
-        Library::new()
+        None
     };
-
-    match cli.command {
-        Commands::Show(show_args) => {
-            if show_args.all && show_args.search.is_any_set() {
-                exit_with_error(
-                    clap::error::ErrorKind::ArgumentConflict,
-                    "--all is mutually exclusive with search criteria.",
-                );
-            }
-
-            if show_args.search.is_any_set() {
-                let hits = get_search_hits(&my_lib, show_args.search)?;
-                if hits.is_empty() {
-                    bail!("No books found matching given criteria.");
-                }
-
-                println!("Matched {} book(s) in your library:\n", hits.len());
-                hits.iter().for_each(|b| println!("{b}"));
-            } else {
-                println!("All books in your library:\n");
-                my_lib.all().for_each(|b| println!("{b}"));
-            }
+    // Checks the file as it is on disk, before opening it fixes anything up.
+    if matches!(cli.command, Commands::Verify) {
+        return run_verify(&path);
+    }
+    let (mut my_lib, repaired) = Library::open_optional_with_repairs(&path)?.unwrap_or_default();
+    if let Some(seed) = cli.uuid_seed {
+        my_lib.set_id_generator(IdGenerator::Seeded(seed));
+    }
+    if !cli.no_normalise {
+        let changed = my_lib.normalise();
+        if changed > 0 {
+            eprintln!("Normalised {changed} book(s), the next save will keep the changes.");
         }
-        Commands::Add(add_args) => {
-            let my_book = Book {
-                title: add_args.title,
-                author: Author::from_str(&add_args.author)?,
-                isbn: add_args.isbn.map(|s| Isbn::from_str(&s)).transpose()?,
-                status: add_args.status.to_status(),
-                tags: add_args.tags.into_iter().collect(),
-                ..Default::default()
-            };
-            my_lib.add(my_book);
-            my_lib.save(path)?;
-            println!("Book added!");
-        }
-        Commands::Remove(search_args) => {
-            if !search_args.is_any_set() {
-                exit_with_error(
-                    clap::error::ErrorKind::MissingRequiredArgument,
-                    "no search criteria provided.",
-                );
-            }
-
-            let hits = get_search_hits(&my_lib, search_args)?;
-            let rm_ids = select_books(hits)?;
-            for id in &rm_ids {
-                my_lib.remove(*id)?;
-            }
-            my_lib.save(path)?;
-            println!("{} book(s) removed from your library.", rm_ids.len());
-        }
-        Commands::Update(update_type) => match update_type {
-            UpdateType::Status { status, search } => {
-                if !status.is_set() {
-                    exit_with_error(
-                        clap::error::ErrorKind::MissingRequiredArgument,
-                        concat!(
-                            "the following required arguments were not provided:\n",
-                            "  <--want|--reading|--read>."
-                        ),
-                    );
-                }
-                if !search.is_any_set() {
-                    exit_with_error(
-                        clap::error::ErrorKind::MissingRequiredArgument,
-                        "no search criteria provided.",
-                    );
-                }
+    }
 
-                let new_status = status.to_status();
-                let hits = get_search_hits(&my_lib, search)?;
-                let update_ids = select_books(hits)?;
-                for id in &update_ids {
-                    my_lib.update_status(*id, new_status)?;
-                }
-                my_lib.save(path)?;
-                println!(
-                    "{} book(s)'s status updated to {new_status:?}.",
-                    update_ids.len()
-                );
-            }
-        },
+    let mut session = Session {
+        path,
+        save_mode: cli.save.into(),
+        defer_save: false,
+        log: cli.log,
+        changes: Vec::new(),
+        date_format: validate_date_format(
+            cli.date_format
+                .or_else(|| config.date_format.clone())
+                .as_deref(),
+        ),
+        use_color: cli.color.or(config.color).unwrap_or_default().use_color(),
+        quiet: cli.quiet,
+        clock: Box::new(SystemClock),
+        config,
+        lock,
+    };
+    // Only commands that save keep the new ids, so only they mention them.
+    if repaired > 0 && modifies_library {
+        session.say(format!(
+            "Assigned new ids to {repaired} book(s) with a missing or duplicate id."
+        ));
+    }
+    if let Err(e) = run(&mut my_lib, cli.command, &mut session) {
+        if let Some(clap_err) = e.downcast_ref::<clap::Error>() {
+            clap_err.exit();
+        }
+        return Err(e);
     }
 
     Ok(())
 }
 
-fn exit_with_error(kind: clap::error::ErrorKind, msg: &str) -> ! {
-    let mut cmd = Cli::command();
-    cmd.error(kind, msg).exit();
-}
-
-fn get_search_hits<'a>(lib: &'a Library, search: SearchArgs) -> Result<Vec<&'a Book>, io::Error> {
-    Ok(lib
-        .search(&LibrarySearch {
-            title: search.title,
-            author: search.author,
-            isbn: search.isbn,
-            status: search.status.as_deref().map(Status::from_str).transpose()?,
-            tags: search.tags,
-            ..Default::default()
-        })
-        .collect::<Vec<&Book>>())
+/// Reads the config file at `path`, warning about any keys spine doesn't know.
+fn load_config(path: &Path, required: bool) -> anyhow::Result<Config> {
+    let config = Config::load(path, required)?;
+    for key in config.unknown_keys() {
+        eprintln!(
+            "Warning: unknown setting '{key}' in {}, ignoring it.",
+            path.display()
+        );
+    }
+    Ok(config)
 }
 
-fn select_books(hits: Vec<&Book>) -> Result<Vec<Uuid>, io::Error> {
-    if hits.is_empty() {
-        return Err(io::Error::other("No books found matching given criteria."));
+fn run(my_lib: &mut Library, command: Commands, session: &mut Session) -> anyhow::Result<()> {
+    match command {
+        Commands::Show(show_args) => run_show(my_lib, show_args, session),
+        Commands::Search(search_args) => run_search(my_lib, search_args, session),
+        Commands::Add(add_args) => run_add(my_lib, add_args, session),
+        Commands::Remove(remove_args) => run_remove(my_lib, remove_args, session),
+        Commands::Update(UpdateType::Status(update_args)) => {
+            run_update_status(my_lib, update_args, session)
+        }
+        Commands::Update(UpdateType::Notes(update_args)) => {
+            run_update_notes(my_lib, update_args, session)
+        }
+        Commands::Update(UpdateType::Unarchive(search_args)) => {
+            run_unarchive(my_lib, search_args, session)
+        }
+        Commands::Tag(TagAction::Add(tag_args)) => run_tag(my_lib, tag_args, true, session),
+        Commands::Tag(TagAction::Remove(tag_args)) => run_tag(my_lib, tag_args, false, session),
+        Commands::Clone(clone_args) => run_clone(my_lib, clone_args, session),
+        Commands::Share(search_args) => run_share(my_lib, search_args),
+        Commands::Peek { verbose } => {
+            match my_lib.next_to_read() {
+                Some(book) => print_book(book, verbose, &session.date_format),
+                None => println!("Nothing to read next: you have no books you want to read."),
+            }
+            Ok(())
+        }
+        Commands::Export(export_args) => run_export(my_lib, &export_args, session),
+        Commands::Import(import_args) => run_import(my_lib, &import_args, session),
+        Commands::Diff { file } => run_diff(my_lib, &file),
+        Commands::Goal { set } => run_goal(my_lib, set, session),
+        Commands::Stats { spent: true, .. } => {
+            run_spent(my_lib);
+            Ok(())
+        }
+        Commands::Stats {
+            by_location: true, ..
+        } => {
+            run_by_location(my_lib);
+            Ok(())
+        }
+        Commands::Stats {
+            json, csv, verbose, ..
+        } => run_stats(
+            my_lib,
+            json,
+            csv,
+            verbose.then_some(session.path.as_path()),
+            session.clock.today(),
+        ),
+        Commands::Report { period } => {
+            print!("{}", Report::new(my_lib, period, session.clock.today()));
+            Ok(())
+        }
+        Commands::Profiles => run_profiles(&session.path),
+        Commands::Info => {
+            run_info(my_lib, &session.date_format);
+            Ok(())
+        }
+        Commands::Batch { file } => run_batch(my_lib, &file, session),
+        Commands::Verify => run_verify(&session.path),
     }
-    if hits.len() > 1 {
-        let found_msg = hits
-            .iter()
-            .enumerate()
-            .map(|(i, b)| format!("{}. {}", (i + 1), b.to_string()))
-            .collect::<Vec<String>>()
-            .join("\n");
-        println!(
-            "Please be more specific, found {} matching books:\n\n{}",
-            hits.len(),
-            found_msg,
+}
+
+fn run_show(my_lib: &Library, mut show_args: ShowArgs, session: &Session) -> anyhow::Result<()> {
+    let date_format = &session.date_format;
+    show_args.search.text = show_args.query;
+    if show_args.all && show_args.search.is_any_set() {
+        return Err(usage_error(
+            clap::error::ErrorKind::ArgumentConflict,
+            "--all is mutually exclusive with search criteria.",
+        )
+        .into());
+    }
+    if show_args.count {
+        print_count(my_lib, show_args.search);
+        return Ok(());
+    }
+
+    if show_args.layout.group {
+        let books = if show_args.search.is_any_set() {
+            let hits = get_search_hits(my_lib, show_args.search);
+            if hits.is_empty() {
+                return Err(SpineError::NotFound.into());
+            }
+            hits
+        } else {
+            my_lib.all().filter(|b| !b.archived).collect()
+        };
+        for (status, heading) in [
+            (Status::Reading, "Reading"),
+            (Status::Want, "Want to read"),
+            (Status::Read, "Read"),
+        ] {
+            let mut group: Vec<&Book> = books
+                .iter()
+                .copied()
+                .filter(|b| b.status == status)
+                .collect();
+            if group.is_empty() {
+                continue;
+            }
+            let group_sort = show_args
+                .layout
+                .group_sort
+                .or(session.config.group_sort)
+                .unwrap_or_default();
+            group_sort.for_status(status).sort(&mut group);
+            println!("{heading}:\n");
+            for b in group {
+                print_book(b, show_args.verbose, date_format);
+            }
+            println!();
+        }
+    } else if show_args.search.is_any_set() {
+        let mut hits = get_search_hits(my_lib, show_args.search);
+        if hits.is_empty() {
+            return Err(SpineError::NotFound.into());
+        }
+        if show_args.layout.recent {
+            hits.reverse();
+        }
+
+        session.say(format!("Matched {} book(s) in your library:\n", hits.len()));
+        for b in hits {
+            print_book(b, show_args.verbose, date_format);
+        }
+    } else {
+        session.say("All books in your library:\n");
+        let books: Box<dyn Iterator<Item = &Book>> = if show_args.layout.recent {
+            Box::new(my_lib.all_rev())
+        } else {
+            Box::new(my_lib.all())
+        };
+        for b in books.filter(|b| !b.archived) {
+            print_book(b, show_args.verbose, date_format);
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints how many books match the search, or how many aren't archived if no criteria are set.
+fn print_count(my_lib: &Library, search_args: SearchArgs) {
+    let count = if search_args.is_any_set() {
+        get_search_hits(my_lib, search_args).len()
+    } else {
+        my_lib.all().filter(|b| !b.archived).count()
+    };
+    println!("{count}");
+}
+
+fn run_search(
+    my_lib: &Library,
+    search_args: SearchCommandArgs,
+    session: &Session,
+) -> anyhow::Result<()> {
+    #[cfg(feature = "regex")]
+    if let Some(pattern) = &search_args.regex {
+        return run_search_regex(
+            my_lib,
+            pattern,
+            search_args.regex_author,
+            search_args.count,
+            session,
         );
-        println!("\nWhich books? (if multiple, separate numbers by commas):");
+    }
+    let SearchCommandArgs {
+        count,
+        query,
+        search: mut search_args,
+        ..
+    } = search_args;
+    search_args.text = query;
+    if !search_args.is_any_set() {
+        return Err(usage_error(
+            clap::error::ErrorKind::MissingRequiredArgument,
+            "no search criteria provided.",
+        )
+        .into());
+    }
+    if count {
+        print_count(my_lib, search_args);
+        return Ok(());
+    }
+
+    let (title_query, author_query) = highlight_needles(&search_args);
+    let hits = get_search_hits(my_lib, search_args);
+    if hits.is_empty() {
+        return Err(SpineError::NotFound.into());
+    }
+
+    session.say(format!("Matched {} book(s) in your library:\n", hits.len()));
+    for b in hits {
+        let author = b.author.to_string();
+        if session.use_color {
+            println!(
+                "{}, {}",
+                highlight(&b.title, &title_query),
+                highlight(&author, &author_query)
+            );
+        } else {
+            println!("{}, {author}", b.title);
+        }
+    }
+
+    Ok(())
+}
+
+/// Searches titles, and authors if `include_author` is set, with a regular expression,
+/// highlighting the first match in each.
+#[cfg(feature = "regex")]
+fn run_search_regex(
+    my_lib: &Library,
+    pattern: &str,
+    include_author: bool,
+    count: bool,
+    session: &Session,
+) -> anyhow::Result<()> {
+    let regex = crate::pattern::compile(pattern)?;
+    let hits: Vec<&Book> = my_lib.search_regex(&regex, include_author).collect();
+    if count {
+        println!("{}", hits.len());
+        return Ok(());
+    }
+    if hits.is_empty() {
+        return Err(SpineError::NotFound.into());
+    }
 
-        loop {
-            match get_user_selections(&hits) {
-                Ok(uuids) => return Ok(uuids),
-                Err(e) => println!("{e}"),
+    let matched = |s: &str| {
+        regex
+            .find(s)
+            .map(|m| m.as_str().to_owned())
+            .unwrap_or_default()
+    };
+    session.say(format!("Matched {} book(s) in your library:\n", hits.len()));
+    for b in hits {
+        let author = b.author.to_string();
+        if session.use_color {
+            let author_match = if include_author {
+                matched(&author)
+            } else {
+                String::new()
+            };
+            println!(
+                "{}, {}",
+                highlight(&b.title, &matched(&b.title)),
+                highlight(&author, &author_match)
+            );
+        } else {
+            println!("{}, {author}", b.title);
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks a profile name can be used as a file name in the data directory. The name needn't be
+/// UTF-8, as long as it's a valid file name.
+fn parse_profile(name: OsString) -> Result<OsString, String> {
+    let lossy = name.to_string_lossy();
+    if lossy.is_empty() || lossy.starts_with('.') || lossy.contains(['/', '\\']) {
+        return Err("profile names must not be empty, start with '.' or contain slashes".into());
+    }
+    Ok(name)
+}
+
+/// Returns `format` if it can format a date, otherwise warns and returns the ISO format.
+fn validate_date_format(format: Option<&str>) -> String {
+    let Some(format) = format else {
+        return DEFAULT_DATE_FORMAT.to_owned();
+    };
+    let mut sample = String::new();
+    if write!(sample, "{}", NaiveDate::MIN.format(format)).is_err() {
+        eprintln!("Warning: invalid date format '{format}', using {DEFAULT_DATE_FORMAT} instead.");
+        return DEFAULT_DATE_FORMAT.to_owned();
+    }
+    format.to_owned()
+}
+
+/// Returns what to highlight in the title and the author of a search hit.
+///
+/// Without `--title` or `--author`, the positional query could have matched either, so it's
+/// highlighted in both.
+fn highlight_needles(search_args: &SearchArgs) -> (String, String) {
+    let text = if search_args.title.is_none() && search_args.author.is_none() {
+        search_args.text.as_deref()
+    } else {
+        None
+    };
+    let needle = |field: Option<&str>| field.or(text).unwrap_or_default().trim().to_owned();
+
+    (
+        needle(search_args.title.as_deref()),
+        needle(search_args.author.as_deref()),
+    )
+}
+
+/// Returns `haystack` with the first case-insensitive match of `needle` in bold and underlined.
+///
+/// If `needle` is empty or not found, `haystack` is returned unchanged.
+#[must_use]
+pub fn highlight(haystack: &str, needle: &str) -> String {
+    match find_ignore_case(haystack, needle) {
+        Some((start, end)) => format!(
+            "{}{}{}",
+            &haystack[..start],
+            haystack[start..end].bold().underlined(),
+            &haystack[end..]
+        ),
+        None => haystack.to_owned(),
+    }
+}
+
+/// Finds the byte range of the first case-insensitive match of `needle` in `haystack`.
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<(usize, usize)> {
+    if needle.is_empty() {
+        return None;
+    }
+    haystack.char_indices().find_map(|(start, _)| {
+        let mut rest = haystack[start..].char_indices();
+        let mut end = start;
+        for n in needle.chars() {
+            let (offset, h) = rest.next()?;
+            if !h.to_lowercase().eq(n.to_lowercase()) {
+                return None;
             }
+            end = start + offset + h.len_utf8();
         }
+        Some((start, end))
+    })
+}
+
+fn print_book(book: &Book, verbose: bool, date_format: &str) {
+    if verbose {
+        println!("{}\n", format_details(book, date_format));
+    } else {
+        println!("{book}");
+    }
+}
+
+/// Formats a book with one line per stored detail, for verbose output.
+fn format_details(book: &Book, date_format: &str) -> String {
+    let mut lines = vec![book.to_string(), format!("  status: {}", book.status)];
+    if let Some(isbn) = &book.isbn {
+        lines.push(format!("  isbn: {}", isbn.as_str()));
+    }
+    if !book.tags.is_empty() {
+        let mut tags: Vec<_> = book.tags.iter().map(String::as_str).collect();
+        tags.sort_unstable();
+        lines.push(format!("  tags: {}", tags.join(", ")));
+    }
+    if let Some(date) = book.date_added {
+        lines.push(format!("  added: {}", date.format(date_format)));
+    }
+    if let Some(date) = book.date_started {
+        lines.push(format!("  started: {}", date.format(date_format)));
+    }
+    if let Some(date) = book.date_finished {
+        lines.push(format!("  finished: {}", date.format(date_format)));
+    }
+    if let Some(days) = book.days_to_read() {
+        lines.push(format!("  days to read: {days}"));
+    }
+    if book.times_read > 0 {
+        lines.push(format!("  times read: {}", book.times_read));
+    }
+    if let Some(cover) = &book.cover_path {
+        lines.push(format!("  cover: {}", cover.display()));
+    }
+    if let Some(price) = &book.price {
+        lines.push(format!("  price: {price}"));
+    }
+    if let Some(language) = &book.language {
+        lines.push(format!("  language: {language}"));
+    }
+    if let Some(location) = &book.location {
+        lines.push(format!("  location: {location}"));
+    }
+    if let Some(priority) = book.priority {
+        lines.push(format!("  priority: {priority}"));
+    }
+    if let Some(rating) = book.rating {
+        lines.push(format!("  rating: {rating}/{MAX_RATING}"));
+    }
+    if let (Some(page), Some(pages)) = (book.page, book.pages) {
+        lines.push(format!("  progress: page {page} of {pages}"));
+    }
+    if let Some(notes) = &book.notes {
+        lines.push(format!("  notes: {notes}"));
     }
 
-    Ok(vec![hits[0].id])
+    lines.join("\n")
 }
 
-fn get_user_selections(hits: &[&Book]) -> Result<Vec<Uuid>, io::Error> {
-    let mut buffer = String::new();
-    let stdin = io::stdin();
+fn run_add(my_lib: &mut Library, add_args: AddArgs, session: &mut Session) -> anyhow::Result<()> {
+    if add_args.stdin {
+        return run_add_stdin(my_lib, session);
+    }
+    if let Some(isbn) = &add_args.isbn
+        && !Isbn::looks_like_isbn(isbn)
+    {
+        return Err(SpineError::Validation(format!(
+            "'{isbn}' doesn't look like an ISBN, check the order of your arguments."
+        ))
+        .into());
+    }
+    if let Some(cover) = &add_args.cover
+        && !cover.exists()
     {
-        let mut handle = stdin.lock();
-        handle.read_line(&mut buffer)?;
+        eprintln!(
+            "Warning: cover image {} does not exist, saving the path anyway.",
+            cover.display()
+        );
+    }
+    // A status flag overrides the status a shared book had.
+    let (title, author, isbn, default_status) = match add_args.from {
+        Some(shared) => (shared.title, shared.author, shared.isbn, shared.status),
+        None => (
+            add_args.title.unwrap_or_default(),
+            add_args.author.unwrap_or_default(),
+            add_args.isbn,
+            add_args
+                .default_status
+                .or(session.config.default_status)
+                .unwrap_or_default(),
+        ),
+    };
+    let status = add_args.status.to_status(default_status);
+    let mut builder = Book::builder()
+        .title(title)
+        .author(author)
+        .tags(add_args.tags)
+        .date_added(session.clock.today());
+    if let Some(isbn) = isbn {
+        builder = builder.isbn(isbn);
+    }
+    if let Some(cover) = add_args.cover {
+        builder = builder.cover_path(cover);
+    }
+    if let Some(price) = add_args.price {
+        builder = builder.price(price);
+    }
+    if let Some(lang) = add_args.lang {
+        builder = builder.language(lang);
+    }
+    if let Some(location) = add_args.location {
+        builder = builder.location(location);
+    }
+    if let Some(notes) = add_args.notes {
+        builder = builder.notes(notes);
+    }
+    if let Some(priority) = add_args.priority {
+        builder = builder.priority(priority);
+    }
+    let mut my_book = builder.build()?;
+    // A book added as read is finished today and counted as read, as if it had been marked read.
+    if status == Status::Read {
+        my_book.set_status(status, session.clock.today());
+    } else {
+        my_book.status = status;
+    }
+    if let Some(existing) = my_lib.author_with_other_casing(&my_book.author) {
+        if add_args.normalize_author {
+            my_book.author = existing.clone();
+        } else {
+            eprintln!("Did you mean '{existing}'? (existing spelling)");
+        }
+    }
+    if let Some(existing) = my_lib.find_existing(&my_book) {
+        match add_args
+            .duplicates
+            .or(session.config.duplicates)
+            .unwrap_or_default()
+        {
+            DuplicatePolicy::Allow => {}
+            DuplicatePolicy::Warn => {
+                eprintln!("Warning: '{existing}' is already in your library, adding it again.");
+            }
+            DuplicatePolicy::Reject => {
+                return Err(SpineError::Validation(format!(
+                    "'{existing}' is already in your library."
+                ))
+                .into());
+            }
+        }
+    }
+    my_lib.add(my_book);
+    if let Some(added) = my_lib.all().last() {
+        session.record(Operation::Add, added);
+    }
+    session.save(my_lib)?;
+    session.say("Book added!");
+
+    Ok(())
+}
+
+/// Adds the books in a JSON object or array read from stdin, adding none if any is invalid.
+fn run_add_stdin(my_lib: &mut Library, session: &mut Session) -> anyhow::Result<()> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let value: serde_json::Value = serde_json::from_str(&input).map_err(SpineError::from)?;
+    let books: Vec<Book> = if value.is_array() {
+        serde_json::from_value(value).map_err(SpineError::from)?
+    } else {
+        vec![serde_json::from_value(value).map_err(SpineError::from)?]
+    };
+    for (i, book) in books.iter().enumerate() {
+        book.validate()
+            .map_err(|e| SpineError::Validation(format!("book {}: {e}", i + 1)))?;
+    }
+
+    let count = books.len();
+    for mut book in books {
+        book.id = Uuid::nil();
+        for tag in mem::take(&mut book.tags) {
+            book.add_tag(&tag);
+        }
+        book.date_added.get_or_insert_with(|| session.clock.today());
+        my_lib.add(book);
+        if let Some(added) = my_lib.all().last() {
+            session.record(Operation::Add, added);
+        }
+    }
+    session.save(my_lib)?;
+    session.say(format!("{count} book(s) added!"));
+
+    Ok(())
+}
+
+fn run_remove(
+    my_lib: &mut Library,
+    remove_args: RemoveArgs,
+    session: &mut Session,
+) -> anyhow::Result<()> {
+    if !remove_args.search.is_any_set() {
+        return Err(usage_error(
+            clap::error::ErrorKind::MissingRequiredArgument,
+            "no search criteria provided.",
+        )
+        .into());
+    }
+
+    let hits = get_search_hits(my_lib, remove_args.search);
+    let rm_ids = match remove_args.pick.pick(&hits) {
+        Some(book) => vec![book.id],
+        None => select_books(&hits)?,
+    };
+    if remove_args.archive {
+        for id in &rm_ids {
+            my_lib.set_archived(*id, true)?;
+        }
+        for book in rm_ids.iter().filter_map(|id| my_lib.get(*id)) {
+            session.record(Operation::Update, book);
+        }
+        session.save(my_lib)?;
+        session.say(format!("{} book(s) archived.", rm_ids.len()));
+        return Ok(());
+    }
+    for b in hits.iter().filter(|b| rm_ids.contains(&b.id)) {
+        session.record(Operation::Remove, b);
+    }
+    my_lib.retain(|b| !rm_ids.contains(&b.id));
+    session.save(my_lib)?;
+    session.say(format!(
+        "{} book(s) removed from your library.",
+        rm_ids.len()
+    ));
+
+    Ok(())
+}
+
+fn run_unarchive(
+    my_lib: &mut Library,
+    mut search_args: SearchArgs,
+    session: &mut Session,
+) -> anyhow::Result<()> {
+    if !search_args.is_any_set() {
+        return Err(usage_error(
+            clap::error::ErrorKind::MissingRequiredArgument,
+            "no search criteria provided.",
+        )
+        .into());
+    }
+
+    search_args.archived = true;
+    let hits = get_search_hits(my_lib, search_args);
+    let ids = select_books(&hits)?;
+    for id in &ids {
+        my_lib.set_archived(*id, false)?;
+    }
+    for book in ids.iter().filter_map(|id| my_lib.get(*id)) {
+        session.record(Operation::Update, book);
+    }
+    session.save(my_lib)?;
+    session.say(format!("{} book(s) moved out of the archive.", ids.len()));
+
+    Ok(())
+}
+
+fn run_update_status(
+    my_lib: &mut Library,
+    update_args: UpdateStatusArgs,
+    session: &mut Session,
+) -> anyhow::Result<()> {
+    let UpdateStatusArgs {
+        status,
+        reread,
+        all,
+        pick,
+        search,
+    } = update_args;
+    if !status.is_set() {
+        return Err(usage_error(
+            clap::error::ErrorKind::MissingRequiredArgument,
+            concat!(
+                "the following required arguments were not provided:\n",
+                "  <--want|--reading|--read>."
+            ),
+        )
+        .into());
+    }
+    if !search.is_any_set() {
+        return Err(usage_error(
+            clap::error::ErrorKind::MissingRequiredArgument,
+            "no search criteria provided.",
+        )
+        .into());
+    }
+
+    let new_status = status.to_status(Status::Want);
+    let hits = get_search_hits(my_lib, search);
+    let update_ids = if all && !hits.is_empty() {
+        hits.iter().map(|b| b.id).collect()
+    } else if let Some(book) = pick.pick(&hits) {
+        vec![book.id]
+    } else {
+        select_books(&hits)?
+    };
+    let mut updated = Vec::new();
+    for id in update_ids {
+        let changed = if reread {
+            my_lib.reread(id, &*session.clock)?;
+            true
+        } else {
+            my_lib.update_status(id, new_status, &*session.clock)?
+        };
+        if changed {
+            updated.push(id);
+        }
+    }
+    if updated.is_empty() {
+        session.say(format!("Already {new_status}, nothing to update."));
+        return Ok(());
+    }
+    for book in updated.iter().filter_map(|id| my_lib.get(*id)) {
+        session.record(Operation::Update, book);
+    }
+    session.save(my_lib)?;
+    session.say(format!(
+        "{} book(s)'s status updated to {new_status}.",
+        updated.len()
+    ));
+
+    Ok(())
+}
+
+/// Replaces the notes of the matching book, asking which if several match.
+fn run_update_notes(
+    my_lib: &mut Library,
+    update_args: UpdateNotesArgs,
+    session: &mut Session,
+) -> anyhow::Result<()> {
+    let UpdateNotesArgs {
+        notes,
+        pick,
+        search,
+    } = update_args;
+    if !search.is_any_set() {
+        return Err(usage_error(
+            clap::error::ErrorKind::MissingRequiredArgument,
+            "no search criteria provided.",
+        )
+        .into());
+    }
+
+    let hits = get_search_hits(my_lib, search);
+    let update_ids = match pick.pick(&hits) {
+        Some(book) => vec![book.id],
+        None => select_books(&hits)?,
+    };
+    for id in &update_ids {
+        my_lib.update_fields(
+            *id,
+            BookUpdate {
+                notes: Some(notes.clone()),
+                ..Default::default()
+            },
+        )?;
+    }
+    for book in update_ids.iter().filter_map(|id| my_lib.get(*id)) {
+        session.record(Operation::Update, book);
+    }
+    session.save(my_lib)?;
+    session.say(format!("{} book(s)'s notes updated.", update_ids.len()));
+
+    Ok(())
+}
+
+/// Adds the tag to, or removes it from, the matching books and reports how many changed.
+fn run_tag(
+    my_lib: &mut Library,
+    tag_args: TagArgs,
+    add: bool,
+    session: &mut Session,
+) -> anyhow::Result<()> {
+    let TagArgs {
+        tag,
+        all,
+        pick,
+        search,
+    } = tag_args;
+    let Some(tag) = canonical_tag(&tag) else {
+        return Err(SpineError::Validation("Invalid tag: must not be empty.".into()).into());
+    };
+    if !search.is_any_set() {
+        return Err(usage_error(
+            clap::error::ErrorKind::MissingRequiredArgument,
+            "no search criteria provided.",
+        )
+        .into());
+    }
+
+    let hits = get_search_hits(my_lib, search);
+    let tag_ids = if all && !hits.is_empty() {
+        hits.iter().map(|b| b.id).collect()
+    } else if let Some(book) = pick.pick(&hits) {
+        vec![book.id]
+    } else {
+        select_books(&hits)?
+    };
+    let mut updated = Vec::new();
+    for id in tag_ids {
+        let changed = if add {
+            my_lib.tag(id, [tag.clone()])?
+        } else {
+            my_lib.untag(id, slice::from_ref(&tag))?
+        };
+        if changed {
+            updated.push(id);
+        }
+    }
+    if updated.is_empty() {
+        let state = if add { "Already tagged" } else { "Not tagged" };
+        session.say(format!("{state} '{tag}', nothing to update."));
+        return Ok(());
+    }
+    for book in updated.iter().filter_map(|id| my_lib.get(*id)) {
+        session.record(Operation::Update, book);
+    }
+    session.save(my_lib)?;
+    if add {
+        session.say(format!("Tagged {} book(s) with '{tag}'.", updated.len()));
+    } else {
+        session.say(format!("Removed '{tag}' from {} book(s).", updated.len()));
+    }
+
+    Ok(())
+}
+
+/// Adds a copy of the one matching book, with any new title, author or ISBN given.
+fn run_clone(
+    my_lib: &mut Library,
+    clone_args: CloneArgs,
+    session: &mut Session,
+) -> anyhow::Result<()> {
+    let CloneArgs {
+        new_title,
+        new_author,
+        new_isbn,
+        search,
+    } = clone_args;
+    if !search.is_any_set() {
+        return Err(usage_error(
+            clap::error::ErrorKind::MissingRequiredArgument,
+            "no search criteria provided.",
+        )
+        .into());
+    }
+
+    let hits = get_search_hits(my_lib, search);
+    let original_id = resolve_single(&hits)?.id;
+    let update = BookUpdate {
+        title: new_title,
+        author: new_author,
+        isbn: new_isbn,
+        notes: None,
+    };
+    let copy_id = my_lib.duplicate(original_id, update, &*session.clock)?;
+    if let Some(copy) = my_lib.get(copy_id) {
+        session.record(Operation::Add, copy);
+    }
+    session.save(my_lib)?;
+    session.say("Book cloned!");
+
+    Ok(())
+}
+
+/// Prints the snippet of the one matching book.
+fn run_share(my_lib: &Library, search_args: SearchArgs) -> anyhow::Result<()> {
+    if !search_args.is_any_set() {
+        return Err(usage_error(
+            clap::error::ErrorKind::MissingRequiredArgument,
+            "no search criteria provided.",
+        )
+        .into());
+    }
+
+    let hits = get_search_hits(my_lib, search_args);
+    println!("{}", SharedBook::from(resolve_single(&hits)?));
+
+    Ok(())
+}
+
+fn run_import(
+    my_lib: &mut Library,
+    import_args: &ImportArgs,
+    session: &mut Session,
+) -> anyhow::Result<()> {
+    if let Some(titles) = &import_args.titles {
+        return run_import_titles(my_lib, titles, session);
+    }
+    if let Some(csv) = &import_args.csv {
+        return run_import_csv(
+            my_lib,
+            csv,
+            import_args.map,
+            !import_args.no_header,
+            session,
+        );
+    }
+    let Some(file) = &import_args.file else {
+        return Err(SpineError::Validation("no library file to import.".into()).into());
+    };
+    let other = Library::open(file)?;
+    if import_args.on_conflict == ConflictPolicy::Ask {
+        // Not held while waiting on answers; the checked save catches any change made meanwhile.
+        session.lock = None;
+    }
+    let summary = my_lib.merge(
+        other,
+        import_args.on_conflict,
+        &*session.clock,
+        ask_take_incoming,
+    );
+    for (operation, ids) in [
+        (Operation::Add, &summary.added),
+        (Operation::Update, &summary.updated),
+    ] {
+        for book in ids.iter().filter_map(|id| my_lib.get(*id)) {
+            session.record(operation, book);
+        }
+    }
+    session.save(my_lib)?;
+    session.say(format!(
+        "{} book(s) added, {} updated.",
+        summary.added.len(),
+        summary.updated.len()
+    ));
+
+    Ok(())
+}
+
+/// Prints the books only in the library, only in the library at `file`, and in both but changed.
+fn run_diff(my_lib: &Library, file: &Path) -> anyhow::Result<()> {
+    let other = Library::open(file)?;
+    let diff = my_lib.diff(&other);
+    let sections = [
+        ("Only in your library", diff.only_in_self),
+        (&format!("Only in {}", file.display()), diff.only_in_other),
+    ];
+    for (heading, books) in sections {
+        println!("{heading} ({}):", books.len());
+        for b in books {
+            println!("  {b}");
+        }
+        println!();
+    }
+    println!("Changed ({}):", diff.changed.len());
+    for (ours, theirs) in diff.changed {
+        if ours.status == theirs.status {
+            println!("  {ours}");
+        } else {
+            println!("  {ours} ({} -> {})", ours.status, theirs.status);
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds a want-to-read book for each non-blank line of a file, split into title and author at
+/// the first `|`, if any.
+fn run_import_titles(
+    my_lib: &mut Library,
+    path: &Path,
+    session: &mut Session,
+) -> anyhow::Result<()> {
+    let contents = fs::read_to_string(path)?;
+    let mut added = 0;
+    let mut skipped = 0;
+    for line in contents.lines() {
+        let (title, author) = line.split_once('|').unwrap_or((line, ""));
+        if title.trim().is_empty() {
+            skipped += 1;
+            continue;
+        }
+        let author = if author.trim().is_empty() {
+            Author::default()
+        } else {
+            author.parse()?
+        };
+        my_lib.add(Book {
+            title: title.trim().to_owned(),
+            author,
+            status: Status::Want,
+            date_added: Some(session.clock.today()),
+            ..Default::default()
+        });
+        if let Some(book) = my_lib.all().last() {
+            session.record(Operation::Add, book);
+        }
+        added += 1;
+    }
+    session.save(my_lib)?;
+    session.say(format!(
+        "{added} book(s) added, {skipped} blank line(s) skipped."
+    ));
+
+    Ok(())
+}
+
+/// Adds a book for each row of a CSV file, adding none if any row is invalid. Without a
+/// `mapping`, the file is read as a spine CSV export.
+fn run_import_csv(
+    my_lib: &mut Library,
+    path: &Path,
+    mapping: Option<CsvMapping>,
+    has_header: bool,
+    session: &mut Session,
+) -> anyhow::Result<()> {
+    let file = File::open(path)?;
+    let books = match mapping {
+        Some(mapping) => import::read_csv(file, mapping, has_header)?,
+        None => import::read_export_csv(file)?,
+    };
+    let count = books.len();
+    for mut book in books {
+        book.date_added.get_or_insert_with(|| session.clock.today());
+        if book.status == Status::Read {
+            book.date_finished
+                .get_or_insert_with(|| session.clock.today());
+        }
+        my_lib.add(book);
+        if let Some(added) = my_lib.all().last() {
+            session.record(Operation::Add, added);
+        }
+    }
+    session.save(my_lib)?;
+    session.say(format!("{count} book(s) added!"));
+
+    Ok(())
+}
+
+/// Asks whether to take the status of an imported book over the one already in the library,
+/// keeping the library's if stdin is closed.
+fn ask_take_incoming(existing: &Book, incoming: &Book) -> bool {
+    println!(
+        "{existing} is {} in your library but {} in the import.",
+        existing.status, incoming.status
+    );
+    loop {
+        print!("Keep yours or take the import's? [k/i] ");
+        let mut buffer = String::new();
+        if io::stdout().flush().is_err() || io::stdin().read_line(&mut buffer).unwrap_or(0) == 0 {
+            return false;
+        }
+        match buffer.trim().to_lowercase().as_str() {
+            "k" | "keep" => return false,
+            "i" | "incoming" => return true,
+            _ => println!("Please answer k or i."),
+        }
+    }
+}
+
+fn run_export(my_lib: &Library, export_args: &ExportArgs, session: &Session) -> anyhow::Result<()> {
+    let format = export_args.format;
+    let preserve_order = export_args.preserve_order;
+    match &export_args.output {
+        Some(output) => {
+            export::export_to_file(my_lib, format, preserve_order, output).with_context(|| {
+                format!(
+                    "Failed to export to {}, it was left unchanged",
+                    output.display()
+                )
+            })?;
+            session.say(format!("Library exported to {}.", output.display()));
+        }
+        None => export::export(my_lib, format, preserve_order, &mut io::stdout().lock())?,
+    }
+
+    Ok(())
+}
+
+fn run_goal(my_lib: &mut Library, set: Option<u32>, session: &mut Session) -> anyhow::Result<()> {
+    if let Some(goal) = set {
+        my_lib.set_goal(Some(goal));
+        session.save(my_lib)?;
+        session.say(format!("Reading goal set to {goal} book(s) a year."));
+        return Ok(());
+    }
+
+    match my_lib.goal_progress(session.clock.today().year()) {
+        Some(progress) => println!("{progress}"),
+        None => println!("No reading goal set, use `spine goal --set <BOOKS>` to set one."),
+    }
+
+    Ok(())
+}
+
+/// Prints the library's stats, along with the size of the file at `path` if given.
+fn run_stats(
+    my_lib: &Library,
+    json: bool,
+    csv: bool,
+    path: Option<&Path>,
+    today: NaiveDate,
+) -> anyhow::Result<()> {
+    let stats = LibraryStats::new(my_lib, today);
+    if json {
+        stats.write_json(&mut io::stdout().lock())?;
+    } else if csv {
+        stats.write_csv(&mut io::stdout().lock())?;
+    } else {
+        print!("{stats}");
+        if let Some(path) = path {
+            let size = fs::metadata(path).ok().map(|m| m.len());
+            println!("\n{}", stats::format_file_size(size, stats.total));
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the total price of the books bought in each currency.
+fn run_spent(my_lib: &Library) {
+    let spent = stats::spent_by_currency(my_lib);
+    if spent.is_empty() {
+        println!("No prices recorded yet, add one with --price.");
+    }
+    for total in spent {
+        println!("{total}");
+    }
+}
+
+/// Prints whether the library file at `path` passed verification, and any issues found.
+fn run_verify(path: &Path) -> anyhow::Result<()> {
+    let report = verify::verify_file(path)?;
+    print!("{report}");
+    if !report.passed() {
+        return Err(
+            SpineError::Validation(format!("{} failed verification.", path.display())).into(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints the books kept in each location, with how many there are.
+fn run_by_location(my_lib: &Library) {
+    let by_location = stats::books_by_location(my_lib);
+    if by_location.is_empty() {
+        println!("No locations recorded yet, add one with --location.");
+    }
+    for (location, books) in by_location {
+        println!("{location} ({})", books.len());
+        for b in books {
+            println!("  {} by {}", b.title, b.author);
+        }
+    }
+}
+
+/// Prints how many books the library has and when it was created and last saved.
+fn run_info(my_lib: &Library, date_format: &str) {
+    let format_time = |time: Option<DateTime<Utc>>| {
+        time.map_or_else(
+            || "not yet saved".to_owned(),
+            |t| {
+                t.with_timezone(&Local)
+                    .format(&format!("{date_format} %H:%M"))
+                    .to_string()
+            },
+        )
+    };
+    println!("{:<10}{}", "Books", my_lib.all().len());
+    println!("{:<10}{}", "Created", format_time(my_lib.created_at()));
+    println!("{:<10}{}", "Updated", format_time(my_lib.updated_at()));
+}
+
+/// Lists the profiles stored beside the library at `path`, marking the one in use.
+fn run_profiles(path: &Path) -> anyhow::Result<()> {
+    let data_dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut profiles = Vec::new();
+    for entry in fs::read_dir(data_dir)? {
+        let entry_path = entry?.path();
+        // Other JSON files, such as exports, aren't profiles.
+        if entry_path.extension().is_some_and(|e| e == "json")
+            && let Some(stem) = entry_path.file_stem()
+            && Library::open(&entry_path).is_ok()
+        {
+            profiles.push(stem.to_string_lossy().into_owned());
+        }
+    }
+    profiles.sort_unstable();
+
+    let current = path.file_stem().map(|s| s.to_string_lossy());
+    for profile in profiles {
+        let marker = if current.as_deref() == Some(&profile) {
+            "*"
+        } else {
+            " "
+        };
+        println!("{marker} {profile}");
+    }
+
+    Ok(())
+}
+
+fn run_batch(my_lib: &mut Library, file: &Path, session: &mut Session) -> anyhow::Result<()> {
+    let contents = fs::read_to_string(file)?;
+    session.defer_save = true;
+    let mut failed = 0;
+    let mut total = 0;
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        total += 1;
+        let result = split_command_line(line)
+            .and_then(parse_batch_line)
+            .and_then(|command| {
+                if matches!(command, Commands::Batch { .. }) {
+                    return Err(SpineError::Validation(
+                        "batch files cannot run other batch files.".into(),
+                    )
+                    .into());
+                }
+                run(my_lib, command, session)
+            });
+        match result {
+            Ok(()) => session.say(format!("line {}: ok", i + 1)),
+            Err(e) => {
+                failed += 1;
+                let msg = e.to_string();
+                eprintln!("line {}: {}", i + 1, msg.lines().next().unwrap_or_default());
+            }
+        }
+    }
+    session.defer_save = false;
+    session.save(my_lib)?;
+    session.say(format!(
+        "Batch finished: {} succeeded, {failed} failed.",
+        total - failed
+    ));
+    if failed > 0 {
+        return Err(
+            SpineError::Validation(format!("{failed} of {total} batch line(s) failed.")).into(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Splits a batch line into words, honouring single and double quotes.
+fn split_command_line(line: &str) -> anyhow::Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
     }
+    if quote.is_some() {
+        return Err(SpineError::Validation("unterminated quote.".into()).into());
+    }
+    if in_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+/// Parses the words of a batch file line as a command.
+///
+/// Global options such as `--no-save` or `--profile` apply to the whole batch, so giving one on
+/// a line is an error rather than being silently ignored.
+fn parse_batch_line(words: Vec<String>) -> anyhow::Result<Commands> {
+    let mut cmd = Cli::command();
+    let matches = cmd.try_get_matches_from_mut(iter::once("spine".to_owned()).chain(words))?;
+    if let Some(arg) = cmd.get_arguments().find(|arg| {
+        arg.is_global_set()
+            && matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+    }) {
+        return Err(SpineError::Validation(format!(
+            "--{} applies to the whole batch, give it before `batch` instead.",
+            arg.get_long().unwrap_or_default()
+        ))
+        .into());
+    }
+
+    Ok(Cli::from_arg_matches(&matches)?.command)
+}
+
+/// Returns the process exit code for an error, see [`SpineError::exit_code`].
+///
+/// Errors not originating from spine itself exit with 1. Usage errors never get here: clap
+/// reports them and exits with 2, as command-line tools conventionally do, even though spine
+/// otherwise uses 2 for IO failures.
+#[must_use]
+pub fn exit_code(err: &anyhow::Error) -> u8 {
+    err.chain()
+        .find_map(|e| e.downcast_ref::<SpineError>())
+        .map_or(1, SpineError::exit_code)
+}
+
+fn usage_error(kind: clap::error::ErrorKind, msg: &str) -> clap::Error {
+    let mut cmd = Cli::command();
+    cmd.error(kind, msg)
+}
+
+fn get_search_hits(lib: &Library, search: SearchArgs) -> Vec<&Book> {
+    lib.search(&LibrarySearch {
+        title: search.title,
+        author: search.author,
+        isbn: search.isbn,
+        isbn_suffix: search.isbn_suffix,
+        status: search.status,
+        tags: search.tags,
+        text: search.text,
+        added_since: search.added_since,
+        added_until: search.added_until,
+        finished_since: search.finished_since,
+        finished_until: search.finished_until,
+        language: search.lang,
+        location: search.location,
+        archived: search.archived,
+    })
+    .collect::<Vec<&Book>>()
+}
+
+/// Returns the ids of the books to act on, asking which to pick if there are several.
+///
+/// Several matches are an error when stdin isn't a terminal, so scripts don't hang on a prompt.
+fn select_books(hits: &[&Book]) -> Result<Vec<Uuid>, SpineError> {
+    match resolve_single(hits) {
+        Ok(book) => Ok(vec![book.id]),
+        Err(SpineError::Ambiguous(_)) if io::stdin().is_terminal() => {
+            let found_msg = hits
+                .iter()
+                .enumerate()
+                .map(|(i, b)| format!("{}. {}", (i + 1), b))
+                .collect::<Vec<String>>()
+                .join("\n");
+            println!(
+                "Please be more specific, found {} matching books:\n\n{}",
+                hits.len(),
+                found_msg,
+            );
+            println!("\nWhich books? (if multiple, separate numbers by commas):");
+
+            read_selections(&mut io::stdin().lock(), hits)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Reads lines of comma-separated numbers from `input` until one picks only listed books.
+fn read_selections(input: &mut impl BufRead, hits: &[&Book]) -> Result<Vec<Uuid>, SpineError> {
+    loop {
+        let mut buffer = String::new();
+        if input.read_line(&mut buffer)? == 0 {
+            return Err(SpineError::Ambiguous(hits.len()));
+        }
+        match parse_selections(&buffer, hits) {
+            Ok(uuids) => return Ok(uuids),
+            Err(e) => println!("{e}"),
+        }
+    }
+}
+
+fn parse_selections(buffer: &str, hits: &[&Book]) -> Result<Vec<Uuid>, io::Error> {
     let choices = buffer
-        .split(",")
+        .split(',')
         .map(|s| s.trim().parse::<usize>())
         .collect::<Vec<Result<usize, ParseIntError>>>();
     for ch in &choices {
@@ -307,3 +2204,153 @@ fn get_user_selections(hits: &[&Book]) -> Result<Vec<Uuid>, io::Error> {
 
     Ok(uuids)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book_added_on(date: NaiveDate) -> Book {
+        Book {
+            title: "kim".to_owned(),
+            date_added: Some(date),
+            ..Default::default()
+        }
+    }
+
+    fn books_titled(titles: &[&str]) -> Vec<Book> {
+        titles
+            .iter()
+            .map(|title| Book {
+                id: Uuid::new_v4(),
+                title: (*title).to_owned(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn read_selections_picks_second_candidate() {
+        let books = books_titled(&["burmese days", "kim", "animal farm"]);
+        let hits: Vec<&Book> = books.iter().collect();
+
+        let selected = read_selections(&mut &b"2\n"[..], &hits).unwrap();
+
+        assert_eq!(selected, vec![books[1].id]);
+    }
+
+    #[test]
+    fn read_selections_asks_again_after_invalid_selection() {
+        let books = books_titled(&["burmese days", "kim"]);
+        let hits: Vec<&Book> = books.iter().collect();
+
+        let selected = read_selections(&mut &b"3\n1, 2\n"[..], &hits).unwrap();
+
+        assert_eq!(selected, vec![books[0].id, books[1].id]);
+    }
+
+    #[test]
+    fn read_selections_fails_as_ambiguous_at_end_of_input() {
+        let books = books_titled(&["burmese days", "kim"]);
+        let hits: Vec<&Book> = books.iter().collect();
+
+        let err = read_selections(&mut &b""[..], &hits).unwrap_err();
+
+        assert!(matches!(err, SpineError::Ambiguous(2)));
+    }
+
+    #[test]
+    fn format_details_shows_date_in_custom_format() {
+        let book = book_added_on(NaiveDate::from_ymd_opt(2024, 3, 9).unwrap());
+        let date_format = validate_date_format(Some("%d/%m/%Y"));
+
+        let details = format_details(&book, &date_format);
+
+        assert!(details.contains("added: 09/03/2024"), "{details}");
+    }
+
+    #[test]
+    fn format_details_shows_iso_date_by_default() {
+        let book = book_added_on(NaiveDate::from_ymd_opt(2024, 3, 9).unwrap());
+        let date_format = validate_date_format(None);
+
+        let details = format_details(&book, &date_format);
+
+        assert!(details.contains("added: 2024-03-09"), "{details}");
+    }
+
+    #[test]
+    fn format_details_shows_start_and_days_to_read() {
+        let book = Book {
+            date_started: NaiveDate::from_ymd_opt(2024, 2, 20),
+            date_finished: NaiveDate::from_ymd_opt(2024, 3, 9),
+            ..book_added_on(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap())
+        };
+
+        let details = format_details(&book, DEFAULT_DATE_FORMAT);
+
+        assert!(
+            details.contains("  started: 2024-02-20\n  finished: 2024-03-09\n  days to read: 18"),
+            "{details}"
+        );
+    }
+
+    #[test]
+    fn validate_date_format_falls_back_to_iso_if_invalid() {
+        assert_eq!(validate_date_format(Some("%Q")), DEFAULT_DATE_FORMAT);
+        assert_eq!(validate_date_format(Some("%H:%M")), DEFAULT_DATE_FORMAT);
+    }
+
+    #[test]
+    fn highlight_needles_fall_back_to_query_without_title_or_author() {
+        let search_args = SearchArgs {
+            text: Some(" orwell ".to_owned()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            highlight_needles(&search_args),
+            ("orwell".to_owned(), "orwell".to_owned())
+        );
+    }
+
+    #[test]
+    fn highlight_needles_ignore_query_given_title() {
+        let search_args = SearchArgs {
+            title: Some("days".to_owned()),
+            text: Some("orwell".to_owned()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            highlight_needles(&search_args),
+            ("days".to_owned(), String::new())
+        );
+    }
+
+    #[test]
+    fn highlight_styles_present_needle() {
+        let highlighted = highlight("burmese days", "days");
+
+        assert_eq!(
+            highlighted,
+            format!("burmese {}", "days".bold().underlined())
+        );
+    }
+
+    #[test]
+    fn highlight_leaves_haystack_unchanged_if_needle_absent() {
+        let highlighted = highlight("burmese days", "nights");
+
+        assert_eq!(highlighted, "burmese days");
+    }
+
+    #[test]
+    fn highlight_matches_needle_of_different_case() {
+        let highlighted = highlight("Burmese Days", "burmese");
+
+        assert_eq!(
+            highlighted,
+            format!("{} Days", "Burmese".bold().underlined())
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::{Local, NaiveDate};
+
+/// Source of the current date, so code that depends on it can be tested with a fixed date.
+pub trait Clock {
+    fn today(&self) -> NaiveDate;
+}
+
+/// Reads today's date from the system, in the local time zone.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Local::now().date_naive()
+    }
+}
+
+/// Always gives the same date.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedClock(pub NaiveDate);
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        self.0
+    }
+}
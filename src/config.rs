@@ -0,0 +1,246 @@
+//! Defaults for command-line options, read from a `spine.toml` file.
+//!
+//! Saves passing the same flags every time. Flags and their environment variables override the
+//! file, and the file overrides spine's built-in defaults.
+
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer};
+
+use crate::{SpineError, Status, sort::GroupSort};
+
+/// Name of the config file in the config directory.
+pub const CONFIG_FILE: &str = "spine.toml";
+
+/// Settings read from a config file. Each is `None` if the file doesn't set it.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    /// Status `add` gives books when no status flag is given.
+    pub default_status: Option<Status>,
+    /// strftime-style format for showing dates.
+    pub date_format: Option<String>,
+    /// Directory libraries are stored in.
+    pub data_dir: Option<PathBuf>,
+    /// Order of the books under each heading of `show --group`.
+    #[serde(deserialize_with = "parse")]
+    pub group_sort: Option<GroupSort>,
+    #[serde(deserialize_with = "parse")]
+    pub color: Option<ColorChoice>,
+    /// What `add` does with a book already in the library.
+    #[serde(deserialize_with = "parse")]
+    pub duplicates: Option<DuplicatePolicy>,
+
+    /// Keys spine doesn't know about, kept to warn about rather than fail on, as they may be
+    /// typos or settings from a newer version.
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+impl Config {
+    /// Returns where the config file is looked for when `--config` isn't given:
+    /// `$XDG_CONFIG_HOME/spine/spine.toml`, or under `%APPDATA%` or `~/.config` if that isn't
+    /// set.
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        ["XDG_CONFIG_HOME", "APPDATA"]
+            .into_iter()
+            .find_map(|var| env::var_os(var).filter(|dir| !dir.is_empty()))
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+            .map(|dir| dir.join("spine").join(CONFIG_FILE))
+    }
+
+    /// Reads the config file at `path`, or the defaults if there isn't one and `required` is
+    /// false.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or isn't valid, or if it's missing and `required` is true.
+    pub fn load(path: &Path, required: bool) -> Result<Self, SpineError> {
+        match fs::read_to_string(path) {
+            Ok(text) => text.parse().map_err(|e| match e {
+                SpineError::Validation(reason) => {
+                    SpineError::Validation(format!("Invalid config {}: {reason}", path.display()))
+                }
+                e => e,
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => Ok(Self::default()),
+            Err(e) => Err(io::Error::new(
+                e.kind(),
+                format!("Couldn't read config {}: {e}", path.display()),
+            )
+            .into()),
+        }
+    }
+
+    /// Returns the keys in the file that spine doesn't know about.
+    pub fn unknown_keys(&self) -> impl Iterator<Item = &str> {
+        self.unknown.keys().map(String::as_str)
+    }
+}
+
+impl FromStr for Config {
+    type Err = SpineError;
+
+    /// Parses the TOML text of a config file.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s).map_err(|e| SpineError::Validation(e.message().to_owned()))
+    }
+}
+
+/// Deserializes a string setting with its [`FromStr`] implementation, as the command line does.
+fn parse<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err: Display>,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+/// Whether to style output such as search highlights.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ColorChoice {
+    /// Style output unless the `NO_COLOR` environment variable is set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Returns whether output should be styled.
+    #[must_use]
+    pub fn use_color(self) -> bool {
+        match self {
+            Self::Auto => env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = SpineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err(SpineError::Validation(format!(
+                "Invalid color '{s}': expected 'auto', 'always' or 'never'."
+            ))),
+        }
+    }
+}
+
+/// What `add` does with a book that is already in the library, matched by ISBN or else by title
+/// and author as [`crate::Library::find_existing`] does.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DuplicatePolicy {
+    /// Add it again without comment.
+    #[default]
+    Allow,
+    /// Add it again, but warn that it's already there.
+    Warn,
+    /// Don't add it.
+    Reject,
+}
+
+impl FromStr for DuplicatePolicy {
+    type Err = SpineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "allow" => Ok(Self::Allow),
+            "warn" => Ok(Self::Warn),
+            "reject" => Ok(Self::Reject),
+            _ => Err(SpineError::Validation(format!(
+                "Invalid duplicate policy '{s}': expected 'allow', 'warn' or 'reject'."
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::{SortKey, SortOrder};
+    use tempfile::tempdir;
+
+    #[test]
+    fn config_parses_every_setting() {
+        let config: Config = r#"
+            default_status = "reading"
+            date_format = "%d/%m/%Y"
+            data_dir = "books"
+            group_sort = "read=title"
+            color = "never"
+            duplicates = "reject"
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(config.default_status, Some(Status::Reading));
+        assert_eq!(config.date_format.as_deref(), Some("%d/%m/%Y"));
+        assert_eq!(config.data_dir, Some(PathBuf::from("books")));
+        assert_eq!(
+            config.group_sort.map(|g| g.read),
+            Some(SortOrder::ascending(SortKey::Title))
+        );
+        assert_eq!(config.color, Some(ColorChoice::Never));
+        assert_eq!(config.duplicates, Some(DuplicatePolicy::Reject));
+        assert_eq!(config.unknown_keys().count(), 0);
+    }
+
+    #[test]
+    fn config_keeps_unknown_keys_to_warn_about() {
+        let config: Config = "colour = \"never\"\ndate_format = \"%Y\"".parse().unwrap();
+
+        assert_eq!(config.unknown_keys().collect::<Vec<_>>(), ["colour"]);
+        assert_eq!(config.date_format.as_deref(), Some("%Y"));
+    }
+
+    #[test]
+    fn config_rejects_invalid_value() {
+        let err = Config::from_str("duplicates = \"sometimes\"").unwrap_err();
+
+        assert!(matches!(err, SpineError::Validation(_)));
+        assert!(
+            err.to_string().contains("Invalid duplicate policy"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn load_returns_defaults_for_absent_file_unless_required() {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join(CONFIG_FILE);
+
+        assert_eq!(Config::load(&path, false).unwrap(), Config::default());
+        assert!(matches!(
+            Config::load(&path, true),
+            Err(SpineError::Io(e)) if e.kind() == io::ErrorKind::NotFound
+        ));
+    }
+
+    #[test]
+    fn load_names_file_with_invalid_toml() {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join(CONFIG_FILE);
+        fs::write(&path, "date_format = ").unwrap();
+
+        let err = Config::load(&path, false).unwrap_err();
+
+        assert!(err.to_string().starts_with("Invalid config "), "{err}");
+    }
+}
@@ -0,0 +1,62 @@
+use std::io;
+
+use thiserror::Error;
+
+/// Errors produced by spine.
+#[derive(Debug, Error)]
+pub enum SpineError {
+    #[error("No books found.")]
+    NotFound,
+
+    #[error("Found {0} matching books, please be more specific.")]
+    Ambiguous(usize),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("Library is in use by another spine process, please try again.")]
+    Locked,
+
+    #[error("Library file changed since it was loaded, use --force to overwrite it.")]
+    Changed,
+}
+
+impl SpineError {
+    /// Returns the process exit code for this error.
+    ///
+    /// - 1: no matching book, or too many matching books
+    /// - 2: IO or (de)serialization failure, or the library is locked or changed on disk
+    /// - 3: invalid input
+    #[must_use]
+    pub const fn exit_code(&self) -> u8 {
+        match self {
+            Self::NotFound | Self::Ambiguous(_) => 1,
+            Self::Io(_) | Self::Serialization(_) | Self::Locked | Self::Changed => 2,
+            Self::Validation(_) => 3,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_code_maps_each_kind_of_error() {
+        let codes = [
+            SpineError::NotFound,
+            SpineError::Ambiguous(2),
+            SpineError::Io(io::Error::other("disk full")),
+            SpineError::Validation("bad isbn".into()),
+        ]
+        .map(|e| e.exit_code());
+
+        assert_eq!(codes, [1, 1, 2, 3]);
+    }
+}
@@ -0,0 +1,174 @@
+//! Notifications of changes to a [`Library`], for embedders such as a GUI that need to redraw
+//! when its books change.
+
+use std::{fmt, sync::Arc};
+
+use uuid::Uuid;
+
+use crate::{Library, Status};
+
+/// A change made to a library, naming the book it affected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LibraryEvent {
+    Added(Uuid),
+    Removed(Uuid),
+    /// The book's status changed, with the side effects of [`crate::Book::set_status`].
+    StatusChanged {
+        id: Uuid,
+        from: Status,
+        to: Status,
+    },
+    /// Any other detail of the book changed, such as its title, tags or whether it's archived.
+    Updated(Uuid),
+}
+
+/// A function called with each [`LibraryEvent`].
+type ObserverFn = dyn Fn(&LibraryEvent) + Send + Sync;
+
+/// The function a library calls with each [`LibraryEvent`], if one is set.
+///
+/// Shared by clones of the library, and ignored when comparing libraries.
+#[derive(Clone, Default)]
+pub(crate) struct Observer(Option<Arc<ObserverFn>>);
+
+impl Observer {
+    /// Calls the observer with `event`, if there is one.
+    pub(crate) fn emit(&self, event: LibraryEvent) {
+        if let Some(observer) = &self.0 {
+            observer(&event);
+        }
+    }
+}
+
+impl fmt::Debug for Observer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(if self.0.is_some() {
+            "Observer(Some(..))"
+        } else {
+            "Observer(None)"
+        })
+    }
+}
+
+impl PartialEq for Observer {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Observer {}
+
+impl Library {
+    /// Calls `observer` with every change made through the library's methods from now on,
+    /// replacing any observer already set.
+    pub fn set_observer(&mut self, observer: impl Fn(&LibraryEvent) + Send + Sync + 'static) {
+        self.observer = Observer(Some(Arc::new(observer)));
+    }
+
+    /// Stops reporting changes to the observer set with [`Library::set_observer`].
+    pub fn clear_observer(&mut self) {
+        self.observer = Observer(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, ConflictPolicy, clock::FixedClock};
+    use chrono::NaiveDate;
+    use std::sync::Mutex;
+
+    /// Returns a library that records its events in the returned list.
+    fn observed_library() -> (Library, Arc<Mutex<Vec<LibraryEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut library = Library::new();
+        let recorded = Arc::clone(&events);
+        library.set_observer(move |event| recorded.lock().unwrap().push(*event));
+        (library, events)
+    }
+
+    fn book(title: &str) -> Book {
+        Book {
+            title: title.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn add_and_remove_fire_events_in_order() {
+        let (mut library, events) = observed_library();
+
+        library.add(book("kim"));
+        library.add(book("emma"));
+        let ids: Vec<Uuid> = library.all().map(|b| b.id).collect();
+        library.remove(ids[0]).unwrap();
+        library.retain(|_| false);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                LibraryEvent::Added(ids[0]),
+                LibraryEvent::Added(ids[1]),
+                LibraryEvent::Removed(ids[0]),
+                LibraryEvent::Removed(ids[1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn status_change_fires_event_only_when_status_differs() {
+        let (mut library, events) = observed_library();
+        library.add(book("kim"));
+        let id = library.all().next().unwrap().id;
+        let clock = FixedClock(NaiveDate::from_ymd_opt(2025, 3, 9).unwrap());
+
+        library.update_status(id, Status::Reading, &clock).unwrap();
+        library.update_status(id, Status::Reading, &clock).unwrap();
+        library.tag(id, ["classic".to_owned()]).unwrap();
+
+        assert_eq!(
+            events.lock().unwrap()[1..],
+            [
+                LibraryEvent::StatusChanged {
+                    id,
+                    from: Status::Want,
+                    to: Status::Reading,
+                },
+                LibraryEvent::Updated(id),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_taking_incoming_status_fires_status_change() {
+        let (mut library, events) = observed_library();
+        library.add(book("kim"));
+        let id = library.all().next().unwrap().id;
+        let incoming = Library::from_books(vec![Book {
+            status: Status::Read,
+            ..book("kim")
+        }]);
+        let clock = FixedClock(NaiveDate::from_ymd_opt(2025, 3, 9).unwrap());
+
+        library.merge(incoming, ConflictPolicy::Furthest, &clock, |_, _| false);
+
+        assert_eq!(
+            events.lock().unwrap()[1..],
+            [LibraryEvent::StatusChanged {
+                id,
+                from: Status::Want,
+                to: Status::Read,
+            }]
+        );
+    }
+
+    #[test]
+    fn cleared_observer_is_not_called() {
+        let (mut library, events) = observed_library();
+
+        library.clear_observer();
+        library.add(book("kim"));
+
+        assert!(events.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,513 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufWriter, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use tempfile::NamedTempFile;
+
+use crate::{Book, Library, SpineError, Status};
+
+/// File formats a library can be exported to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Markdown,
+    Json,
+    Bibtex,
+    /// Markdown list of read books by the month they were finished.
+    Timeline,
+}
+
+impl FromStr for ExportFormat {
+    type Err = SpineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "json" => Ok(Self::Json),
+            "bibtex" | "bib" => Ok(Self::Bibtex),
+            "timeline" => Ok(Self::Timeline),
+            _ => Err(SpineError::Validation(
+                "Invalid format: expected 'csv', 'markdown', 'json', 'bibtex', or 'timeline'"
+                    .into(),
+            )),
+        }
+    }
+}
+
+/// Writes the library in the given format.
+///
+/// Books are sorted by author then title, ignoring a leading "The", "A" or "An", so the output
+/// doesn't depend on the order they were added, unless `preserve_order` is set.
+///
+/// # Errors
+///
+/// Fails if `writer` can't be written to.
+pub fn export(
+    library: &Library,
+    format: ExportFormat,
+    preserve_order: bool,
+    writer: &mut impl Write,
+) -> Result<(), SpineError> {
+    let books = ordered_books(library, preserve_order);
+    match format {
+        ExportFormat::Csv => export_csv(&books, writer),
+        ExportFormat::Markdown => export_markdown(&books, writer),
+        ExportFormat::Json => export_json(&books, writer),
+        ExportFormat::Bibtex => export_bibtex(&books, writer),
+        ExportFormat::Timeline => export_timeline(&books, writer),
+    }
+}
+
+/// Exports the library to a file like [`export`], replacing the file only once the whole export
+/// has been written, so a failure part way through leaves any existing file as it was.
+///
+/// # Errors
+///
+/// Fails if the file can't be written.
+pub fn export_to_file(
+    library: &Library,
+    format: ExportFormat,
+    preserve_order: bool,
+    path: &Path,
+) -> Result<(), SpineError> {
+    write_atomically(path, |mut writer| {
+        export(library, format, preserve_order, &mut writer)
+    })
+}
+
+/// Writes a file by calling `write` on a temporary file beside `path`, then moving it into place
+/// only if `write` and syncing it to disk succeed. The temporary file is removed otherwise.
+pub(crate) fn write_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut dyn Write) -> Result<(), SpineError>,
+) -> Result<(), SpineError> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut file = NamedTempFile::new_in(dir)?;
+    // Temporary files are only readable by their owner, unlike the file being replaced.
+    match fs::metadata(path) {
+        Ok(existing) => file.as_file().set_permissions(existing.permissions())?,
+        #[cfg(unix)]
+        Err(_) => {
+            use std::os::unix::fs::PermissionsExt;
+            file.as_file()
+                .set_permissions(fs::Permissions::from_mode(0o644))?;
+        }
+        #[cfg(not(unix))]
+        Err(_) => {}
+    }
+    {
+        let mut buf = BufWriter::new(file.as_file_mut());
+        write(&mut buf)?;
+        buf.flush()?;
+    }
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| e.error)?;
+
+    Ok(())
+}
+
+fn ordered_books(library: &Library, preserve_order: bool) -> Vec<&Book> {
+    let mut books: Vec<&Book> = library.all().collect();
+    if !preserve_order {
+        books.sort_by_cached_key(|b| {
+            (
+                b.author.surname.to_lowercase(),
+                b.author.first_name.to_lowercase(),
+                b.title_sort_key(),
+                b.id,
+            )
+        });
+    }
+    books
+}
+
+fn sorted_tags(book: &Book) -> Vec<&str> {
+    let mut tags: Vec<&str> = book.tags.iter().map(String::as_str).collect();
+    tags.sort_unstable();
+    tags
+}
+
+fn export_csv(books: &[&Book], writer: &mut impl Write) -> Result<(), SpineError> {
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    csv_writer
+        .write_record([
+            "title",
+            "author",
+            "isbn",
+            "status",
+            "tags",
+            "date_added",
+            "date_finished",
+        ])
+        .map_err(io_error)?;
+    for b in books {
+        csv_writer
+            .write_record([
+                b.title.clone(),
+                b.author.to_string(),
+                b.isbn
+                    .as_ref()
+                    .map(|i| i.as_str().to_owned())
+                    .unwrap_or_default(),
+                b.status.as_str().to_owned(),
+                sorted_tags(b).join(";"),
+                b.date_added.map(|d| d.to_string()).unwrap_or_default(),
+                b.date_finished.map(|d| d.to_string()).unwrap_or_default(),
+            ])
+            .map_err(io_error)?;
+    }
+    csv_writer.flush()?;
+
+    Ok(())
+}
+
+fn export_markdown(books: &[&Book], writer: &mut impl Write) -> Result<(), SpineError> {
+    writeln!(writer, "# My Library")?;
+    for (status, heading) in [
+        (Status::Reading, "Reading"),
+        (Status::Want, "Want to read"),
+        (Status::Read, "Read"),
+    ] {
+        let group: Vec<_> = books.iter().filter(|b| b.status == status).collect();
+        if group.is_empty() {
+            continue;
+        }
+        writeln!(writer, "\n## {heading}\n")?;
+        for b in group {
+            write!(writer, "- *{}* by {}", b.title, b.author)?;
+            let tags = sorted_tags(b);
+            if !tags.is_empty() {
+                write!(writer, " ({})", tags.join(", "))?;
+            }
+            writeln!(writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Lists the read books in the order they were finished, under a heading for each month, with
+/// books without a finish date under "Undated" at the end.
+fn export_timeline(books: &[&Book], writer: &mut impl Write) -> Result<(), SpineError> {
+    let mut read: Vec<_> = books.iter().filter(|b| b.status == Status::Read).collect();
+    read.sort_by_key(|b| (b.date_finished.is_none(), b.date_finished));
+    writeln!(writer, "# Reading timeline")?;
+    let mut current_heading = None;
+    for b in read {
+        let heading = b
+            .date_finished
+            .map_or_else(|| "Undated".to_owned(), |d| d.format("%B %Y").to_string());
+        if current_heading.as_ref() != Some(&heading) {
+            writeln!(writer, "\n## {heading}\n")?;
+            current_heading = Some(heading);
+        }
+        match b.date_finished {
+            Some(date) => writeln!(writer, "- {date}: *{}* by {}", b.title, b.author)?,
+            None => writeln!(writer, "- *{}* by {}", b.title, b.author)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn export_json(books: &[&Book], writer: &mut impl Write) -> Result<(), SpineError> {
+    serde_json::to_writer_pretty(&mut *writer, books)?;
+    writeln!(writer)?;
+
+    Ok(())
+}
+
+fn export_bibtex(books: &[&Book], writer: &mut impl Write) -> Result<(), SpineError> {
+    let mut used_keys: HashMap<String, usize> = HashMap::new();
+    for (i, b) in books.iter().enumerate() {
+        let base_key = bibtex_key(b);
+        let count = used_keys.entry(base_key.clone()).or_default();
+        *count += 1;
+        let key = if *count == 1 {
+            base_key
+        } else {
+            format!("{base_key}{count}")
+        };
+
+        if i > 0 {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "@book{{{key},")?;
+        writeln!(writer, "  title = {{{}}},", b.title)?;
+        if b.author.surname.is_empty() {
+            writeln!(writer, "  author = {{{}}},", b.author.first_name)?;
+        } else {
+            writeln!(
+                writer,
+                "  author = {{{}, {}}},",
+                b.author.surname, b.author.first_name
+            )?;
+        }
+        if let Some(isbn) = &b.isbn {
+            writeln!(writer, "  isbn = {{{}}},", isbn.as_str())?;
+        }
+        writeln!(writer, "}}")?;
+    }
+
+    Ok(())
+}
+
+/// Builds a citation key like `orwell_burmese` from the author's surname and first title word.
+fn bibtex_key(book: &Book) -> String {
+    let name = if book.author.surname.is_empty() {
+        &book.author.first_name
+    } else {
+        &book.author.surname
+    };
+    let first_word = book.title.split_whitespace().next().unwrap_or_default();
+
+    [name.as_str(), first_word]
+        .iter()
+        .map(|part| {
+            part.chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn io_error(err: csv::Error) -> SpineError {
+    SpineError::Io(err.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Author, Isbn};
+    use std::collections::HashSet;
+    use uuid::uuid;
+
+    fn books() -> Vec<Book> {
+        vec![
+            Book {
+                id: uuid!("a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8"),
+                title: "kim".to_owned(),
+                author: Author::from_str("rudyard kipling").unwrap(),
+                isbn: Some(Isbn::from_str("9780199536467").unwrap()),
+                status: Status::Read,
+                tags: HashSet::from(["classic".into(), "1800s".into(), "india".into()]),
+                ..Default::default()
+            },
+            Book {
+                id: uuid!("b1b2b3b4-c1c2-d1d2-e1e2-e3e4e5e6e7e8"),
+                title: "burmese days".to_owned(),
+                author: Author::from_str("george orwell").unwrap(),
+                ..Default::default()
+            },
+            Book {
+                id: uuid!("c1c2c3c4-d1d2-e1e2-f1f2-f3f4f5f6f7f8"),
+                title: "animal farm".to_owned(),
+                author: Author::from_str("george orwell").unwrap(),
+                status: Status::Reading,
+                ..Default::default()
+            },
+        ]
+    }
+
+    fn export_to_string(library: &Library, format: ExportFormat, preserve_order: bool) -> String {
+        let mut out = Vec::new();
+        export(library, format, preserve_order, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn export_is_identical_regardless_of_insertion_order() {
+        let mut forwards = Library::new();
+        let mut backwards = Library::new();
+        for b in books() {
+            forwards.add(b);
+        }
+        for b in books().into_iter().rev() {
+            backwards.add(b);
+        }
+
+        for format in [
+            ExportFormat::Csv,
+            ExportFormat::Markdown,
+            ExportFormat::Json,
+            ExportFormat::Bibtex,
+            ExportFormat::Timeline,
+        ] {
+            assert_eq!(
+                export_to_string(&forwards, format, false),
+                export_to_string(&backwards, format, false),
+                "{format:?} export differs"
+            );
+        }
+    }
+
+    #[test]
+    fn export_timeline_groups_read_books_by_month_finished() {
+        let mut library = Library::new();
+        for (title, finished) in [
+            ("animal farm", Some("2025-03-20")),
+            ("burmese days", None),
+            ("coming up for air", Some("2024-12-01")),
+            ("homage to catalonia", Some("2025-03-02")),
+        ] {
+            library.add(Book {
+                title: title.to_owned(),
+                author: Author::from_str("george orwell").unwrap(),
+                status: Status::Read,
+                date_finished: finished.map(|d| d.parse().unwrap()),
+                ..Default::default()
+            });
+        }
+        library.add(books()[1].clone());
+
+        let timeline = export_to_string(&library, ExportFormat::Timeline, true);
+
+        assert_eq!(
+            timeline,
+            concat!(
+                "# Reading timeline\n",
+                "\n## December 2024\n\n",
+                "- 2024-12-01: *coming up for air* by george orwell\n",
+                "\n## March 2025\n\n",
+                "- 2025-03-02: *homage to catalonia* by george orwell\n",
+                "- 2025-03-20: *animal farm* by george orwell\n",
+                "\n## Undated\n\n",
+                "- *burmese days* by george orwell\n",
+            )
+        );
+    }
+
+    #[test]
+    fn export_sorts_titles_ignoring_leading_article() {
+        let mut library = Library::new();
+        for title in ["Zoo", "The Hobbit", "Hobbit-Forming", "Animal Farm"] {
+            library.add(Book {
+                title: title.to_owned(),
+                author: Author::from_str("george orwell").unwrap(),
+                ..Default::default()
+            });
+        }
+
+        let csv = export_to_string(&library, ExportFormat::Csv, false);
+
+        let titles: Vec<_> = csv
+            .lines()
+            .skip(1)
+            .map(|l| l.split(',').next().unwrap())
+            .collect();
+        assert_eq!(
+            titles,
+            vec!["Animal Farm", "The Hobbit", "Hobbit-Forming", "Zoo"]
+        );
+    }
+
+    #[test]
+    fn export_to_file_writes_whole_export() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("books.csv");
+        let mut library = Library::new();
+        for b in books() {
+            library.add(b);
+        }
+
+        export_to_file(&library, ExportFormat::Csv, false, &path).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            export_to_string(&library, ExportFormat::Csv, false)
+        );
+    }
+
+    #[test]
+    fn write_atomically_leaves_no_partial_file_when_writer_fails() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("books.csv");
+
+        let err = write_atomically(&path, |writer| {
+            writer.write_all(b"title,author\n")?;
+            Err(std::io::Error::from(std::io::ErrorKind::StorageFull).into())
+        })
+        .unwrap_err();
+
+        assert!(matches!(err, SpineError::Io(e) if e.kind() == std::io::ErrorKind::StorageFull));
+        assert_eq!(std::fs::read_dir(tmp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomically_keeps_permissions_of_replaced_file() {
+        use std::os::unix::fs::PermissionsExt;
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("books.csv");
+        std::fs::write(&path, "old export").unwrap();
+        std::fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+        write_atomically(&path, |writer| Ok(writer.write_all(b"new")?)).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+
+    #[test]
+    fn write_atomically_keeps_existing_file_when_writer_fails() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("books.csv");
+        std::fs::write(&path, "old export").unwrap();
+
+        write_atomically(&path, |writer| {
+            writer.write_all(b"new")?;
+            Err(SpineError::Validation("failed".into()))
+        })
+        .unwrap_err();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "old export");
+    }
+
+    #[test]
+    fn export_csv_preserves_insertion_order_if_asked() {
+        let mut library = Library::new();
+        for b in books() {
+            library.add(b);
+        }
+
+        let csv = export_to_string(&library, ExportFormat::Csv, true);
+
+        let titles: Vec<_> = csv
+            .lines()
+            .skip(1)
+            .map(|l| l.split(',').next().unwrap())
+            .collect();
+        assert_eq!(titles, vec!["kim", "burmese days", "animal farm"]);
+    }
+
+    #[test]
+    fn export_csv_sorts_by_author_then_title() {
+        let mut library = Library::new();
+        for b in books() {
+            library.add(b);
+        }
+
+        let csv = export_to_string(&library, ExportFormat::Csv, false);
+
+        assert_eq!(
+            csv,
+            concat!(
+                "title,author,isbn,status,tags,date_added,date_finished\n",
+                "kim,rudyard kipling,9780199536467,read,1800s;classic;india,,\n",
+                "animal farm,george orwell,,reading,,,\n",
+                "burmese days,george orwell,,want,,,\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use uuid::{Builder, Uuid};
+
+/// Source of the ids given to new books.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum IdGenerator {
+    /// Random version 4 UUIDs.
+    #[default]
+    Random,
+    /// Version 4 UUIDs from a pseudo-random sequence, the same for the same starting seed, so
+    /// tests can predict ids.
+    Seeded(u64),
+}
+
+impl IdGenerator {
+    pub fn next_id(&mut self) -> Uuid {
+        match self {
+            Self::Random => Uuid::new_v4(),
+            Self::Seeded(state) => {
+                let mut bytes = [0; 16];
+                bytes[..8].copy_from_slice(&split_mix(state).to_le_bytes());
+                bytes[8..].copy_from_slice(&split_mix(state).to_le_bytes());
+                Builder::from_random_bytes(bytes).into_uuid()
+            }
+        }
+    }
+}
+
+/// Advances `state` and returns the next number of the `SplitMix64` sequence.
+const fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_gives_same_ids_for_same_seed() {
+        let run = |seed| {
+            let mut ids = IdGenerator::Seeded(seed);
+            [ids.next_id(), ids.next_id(), ids.next_id()]
+        };
+
+        let first = run(7);
+
+        assert_eq!(first, run(7));
+        assert_ne!(first, run(8));
+        assert_ne!(first[0], first[1]);
+        assert_eq!(first[0].get_version_num(), 4);
+    }
+}
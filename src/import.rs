@@ -0,0 +1,364 @@
+use std::{io::Read, str::FromStr};
+
+use chrono::NaiveDate;
+use csv::StringRecord;
+
+use crate::{Book, SpineError, Status};
+
+/// Which 1-based CSV columns hold each field of a book, parsed from e.g.
+/// `title=1,author=2,isbn=3,status=4`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CsvMapping {
+    pub title: usize,
+    pub author: usize,
+    pub isbn: Option<usize>,
+    pub status: Option<usize>,
+    /// Tags separated by ";", as [`crate::export`] writes them.
+    pub tags: Option<usize>,
+    pub date_added: Option<usize>,
+    pub date_finished: Option<usize>,
+}
+
+impl CsvMapping {
+    /// Finds the column of each field in a header row named as [`crate::export`] names them, in
+    /// any order and ignoring case. Columns with other names are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`SpineError::Validation`] if there is no title or author column.
+    pub fn from_header(header: &StringRecord) -> Result<Self, SpineError> {
+        let column = |field: &str| {
+            header
+                .iter()
+                .position(|name| name.trim().eq_ignore_ascii_case(field))
+                .map(|i| i + 1)
+        };
+        let required = |field: &str| {
+            column(field).ok_or_else(|| {
+                SpineError::Validation(format!("Missing column '{field}' in CSV header."))
+            })
+        };
+
+        Ok(Self {
+            title: required("title")?,
+            author: required("author")?,
+            isbn: column("isbn"),
+            status: column("status"),
+            tags: column("tags"),
+            date_added: column("date_added"),
+            date_finished: column("date_finished"),
+        })
+    }
+}
+
+impl FromStr for CsvMapping {
+    type Err = SpineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mapping = Self::default();
+        let (mut title, mut author) = (None, None);
+        for pair in s.split(',') {
+            let (field, column) = pair.split_once('=').ok_or_else(|| {
+                SpineError::Validation(format!(
+                    "Invalid column mapping '{}': expected field=column.",
+                    pair.trim()
+                ))
+            })?;
+            let column = match column.trim().parse::<usize>() {
+                Ok(column) if column > 0 => column,
+                _ => {
+                    return Err(SpineError::Validation(format!(
+                        "Invalid column '{}': columns are numbered from 1.",
+                        column.trim()
+                    )));
+                }
+            };
+            let slot = match field.trim().to_lowercase().as_str() {
+                "title" => &mut title,
+                "author" => &mut author,
+                "isbn" => &mut mapping.isbn,
+                "status" => &mut mapping.status,
+                "tags" => &mut mapping.tags,
+                "date_added" => &mut mapping.date_added,
+                "date_finished" => &mut mapping.date_finished,
+                other => {
+                    return Err(SpineError::Validation(format!(
+                        "Invalid field '{other}': expected 'title', 'author', 'isbn', 'status', \
+                         'tags', 'date_added', or 'date_finished'."
+                    )));
+                }
+            };
+            *slot = Some(column);
+        }
+        let required = |column: Option<usize>, field: &str| {
+            column.ok_or_else(|| {
+                SpineError::Validation(format!("Missing column mapping for {field}."))
+            })
+        };
+
+        Ok(Self {
+            title: required(title, "title")?,
+            author: required(author, "author")?,
+            ..mapping
+        })
+    }
+}
+
+/// Reads a book from each row of a CSV, taking fields from the columns in `mapping` and ignoring
+/// the rest.
+///
+/// Books read count as read once, finished on the date in the `date_finished` column if there
+/// is one.
+///
+/// # Errors
+///
+/// Fails on the first row that is missing a mapped column or doesn't make a valid book, so that
+/// either every row is read or none is.
+pub fn read_csv(
+    reader: impl Read,
+    mapping: CsvMapping,
+    has_header: bool,
+) -> Result<Vec<Book>, SpineError> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .has_headers(has_header)
+        .flexible(true)
+        .from_reader(reader);
+    read_records(&mut csv_reader, mapping)
+}
+
+/// Reads a CSV written by [`crate::export`], finding each field's column from the header so
+/// columns may be reordered or added, e.g. after editing the file in a spreadsheet.
+///
+/// Every field the export writes is read back, so an unedited export reads back as the books it
+/// was made from, other than their ids and the details the export leaves out.
+///
+/// # Errors
+///
+/// Fails like [`read_csv`], or if the header has no title or author column.
+pub fn read_export_csv(reader: impl Read) -> Result<Vec<Book>, SpineError> {
+    let mut csv_reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let header = csv_reader
+        .headers()
+        .map_err(|e| SpineError::Validation(format!("invalid CSV header: {e}")))?;
+    let mapping = CsvMapping::from_header(header)?;
+    read_records(&mut csv_reader, mapping)
+}
+
+/// Reads a book from each remaining row of `csv_reader`, failing on the first invalid row.
+fn read_records<R: Read>(
+    csv_reader: &mut csv::Reader<R>,
+    mapping: CsvMapping,
+) -> Result<Vec<Book>, SpineError> {
+    let mut books = Vec::new();
+    for (i, record) in csv_reader.records().enumerate() {
+        let row = i + 1;
+        let record =
+            record.map_err(|e| SpineError::Validation(format!("row {row}: invalid CSV: {e}")))?;
+        let cell = |column: usize| {
+            record
+                .get(column - 1)
+                .map(str::trim)
+                .ok_or_else(|| SpineError::Validation(format!("row {row}: no column {column}.")))
+        };
+        let mut builder = Book::builder()
+            .title(cell(mapping.title)?)
+            .author(cell(mapping.author)?);
+        if let Some(column) = mapping.isbn {
+            let isbn = cell(column)?;
+            if !isbn.is_empty() {
+                builder = builder.isbn(isbn);
+            }
+        }
+        if let Some(column) = mapping.status {
+            let status = cell(column)?;
+            if !status.is_empty() {
+                builder = builder.status(
+                    Status::from_str(status)
+                        .map_err(|e| SpineError::Validation(format!("row {row}: {e}")))?,
+                );
+            }
+        }
+        if let Some(column) = mapping.tags {
+            builder = builder.tags(cell(column)?.split(';').map(str::to_owned));
+        }
+        let date = |column: Option<usize>| {
+            column
+                .map(cell)
+                .transpose()?
+                .filter(|date| !date.is_empty())
+                .map(|date| {
+                    NaiveDate::from_str(date).map_err(|_| {
+                        SpineError::Validation(format!(
+                            "row {row}: Invalid date '{date}': expected YYYY-MM-DD."
+                        ))
+                    })
+                })
+                .transpose()
+        };
+        if let Some(date_added) = date(mapping.date_added)? {
+            builder = builder.date_added(date_added);
+        }
+        let mut book = builder
+            .build()
+            .map_err(|e| SpineError::Validation(format!("row {row}: {e}")))?;
+        if book.status == Status::Read {
+            book.times_read = 1;
+            book.date_finished = date(mapping.date_finished)?;
+        }
+        books.push(book);
+    }
+
+    Ok(books)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Author, Isbn, Library,
+        export::{ExportFormat, export},
+    };
+    use std::collections::HashSet;
+    use uuid::Uuid;
+
+    #[test]
+    fn read_csv_maps_custom_columns() {
+        let csv = concat!(
+            "shelf,name,by,state,code\n",
+            "a1,kim,rudyard kipling,Reading,9780199536467\n",
+            "b2,emma,jane austen,read,\n",
+        );
+        let mapping = "title=2,author=3,status=4,isbn=5".parse().unwrap();
+
+        let books = read_csv(csv.as_bytes(), mapping, true).unwrap();
+
+        let fields: Vec<_> = books
+            .iter()
+            .map(|b| {
+                (
+                    b.title.as_str(),
+                    b.author.to_string(),
+                    b.status,
+                    b.isbn.as_ref().map(|i| i.as_str().to_owned()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                (
+                    "kim",
+                    "rudyard kipling".to_owned(),
+                    Status::Reading,
+                    Some("9780199536467".to_owned())
+                ),
+                ("emma", "jane austen".to_owned(), Status::Read, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_csv_counts_read_books_as_read_once() {
+        let csv = concat!(
+            "kim,rudyard kipling,read,2024-03-20\n",
+            "emma,jane austen,want,2024-03-21\n",
+        );
+        let mapping = "title=1,author=2,status=3,date_finished=4".parse().unwrap();
+
+        let books = read_csv(csv.as_bytes(), mapping, false).unwrap();
+
+        assert_eq!(books[0].times_read, 1);
+        assert_eq!(books[0].date_finished, NaiveDate::from_ymd_opt(2024, 3, 20));
+        assert_eq!(books[1].times_read, 0);
+        assert_eq!(books[1].date_finished, None);
+    }
+
+    #[test]
+    fn read_csv_reads_first_row_without_header() {
+        let mapping = "title=1,author=2".parse().unwrap();
+
+        let books = read_csv(&b"kim,rudyard kipling\n"[..], mapping, false).unwrap();
+
+        assert_eq!(books.len(), 1);
+    }
+
+    #[test]
+    fn read_csv_fails_on_missing_column() {
+        let mapping = "title=1,author=4".parse().unwrap();
+
+        let err = read_csv(&b"kim,rudyard kipling\n"[..], mapping, false).unwrap_err();
+
+        assert_eq!(err.to_string(), "row 1: no column 4.");
+    }
+
+    #[test]
+    fn read_export_csv_reads_back_unedited_export() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d);
+        let mut library = Library::new();
+        library.add(Book {
+            title: "kim".to_owned(),
+            author: Author::from_str("rudyard kipling").unwrap(),
+            isbn: Some(Isbn::from_str("9780199536467").unwrap()),
+            status: Status::Read,
+            tags: HashSet::from(["classic".into(), "india".into()]),
+            date_added: day(1),
+            date_finished: day(20),
+            times_read: 1,
+            ..Default::default()
+        });
+        library.add(Book {
+            title: "emma, or \"the matchmaker\"".to_owned(),
+            author: Author::from_str("jane austen").unwrap(),
+            status: Status::Reading,
+            date_added: day(2),
+            ..Default::default()
+        });
+        let mut csv = Vec::new();
+        export(&library, ExportFormat::Csv, true, &mut csv).unwrap();
+
+        let books = read_export_csv(csv.as_slice()).unwrap();
+
+        let without_ids = |books: Vec<Book>| -> Vec<Book> {
+            books
+                .into_iter()
+                .map(|b| Book {
+                    id: Uuid::nil(),
+                    ..b
+                })
+                .collect()
+        };
+        assert_eq!(
+            without_ids(books),
+            without_ids(library.all().cloned().collect())
+        );
+    }
+
+    #[test]
+    fn read_export_csv_finds_reordered_columns_and_leaves_empty_isbn_unset() {
+        let csv = concat!(
+            "Status,shelf,Author,title,isbn\n",
+            "want,a1,rudyard kipling,kim,\n",
+        );
+
+        let books = read_export_csv(csv.as_bytes()).unwrap();
+
+        assert_eq!(books[0].title, "kim");
+        assert_eq!(books[0].author.to_string(), "rudyard kipling");
+        assert_eq!(books[0].isbn, None);
+        assert_eq!(books[0].status, Status::Want);
+    }
+
+    #[test]
+    fn read_export_csv_requires_title_and_author_columns() {
+        let err = read_export_csv(&b"title,writer\nkim,rudyard kipling\n"[..]).unwrap_err();
+
+        assert_eq!(err.to_string(), "Missing column 'author' in CSV header.");
+    }
+
+    #[test]
+    fn csv_mapping_requires_title_and_author() {
+        let err = CsvMapping::from_str("title=1,isbn=2").unwrap_err();
+
+        assert_eq!(err.to_string(), "Missing column mapping for author.");
+    }
+}
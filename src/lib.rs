@@ -1,124 +1,882 @@
+// Tests unwrap freely, as a panic is how they fail.
+#![cfg_attr(test, allow(clippy::unwrap_used))]
+
 use std::{
-    fs::{File, OpenOptions},
-    io::{self, BufReader, BufWriter},
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::{self, Display},
+    fs::File,
+    io::{self, BufReader, Read},
+    iter::Rev,
     path::Path,
     slice,
+    str::FromStr,
+    time::SystemTime,
 };
 
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc, Weekday};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-pub use crate::book::{Author, Book, Isbn, Status};
+pub use crate::book::{Author, Book, BookBuilder, BookSummary, Isbn, Money, Status};
+pub use crate::error::SpineError;
+
+use crate::{
+    clock::Clock,
+    events::{LibraryEvent, Observer},
+    ids::IdGenerator,
+};
 
+#[cfg(feature = "async")]
+mod async_io;
+pub mod audit;
 pub mod book;
 pub mod cli;
+pub mod clock;
+pub mod config;
+pub mod error;
+pub mod events;
+pub mod export;
+pub mod ids;
+pub mod import;
+pub mod lock;
+#[cfg(feature = "regex")]
+pub mod pattern;
+pub mod prelude;
+pub mod share;
+pub mod sort;
+pub mod stats;
 pub mod tui;
+pub mod verify;
 
-#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Library {
     books: Vec<Book>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    goal: Option<u32>,
+
+    /// When the library was first saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+
+    /// When the library was last saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<DateTime<Utc>>,
+
+    /// Top-level fields spine doesn't know about, such as a hand-added `_comment`, kept so they
+    /// are written back on save. Sorted so saved files don't change between runs.
+    #[serde(flatten)]
+    extra: BTreeMap<String, serde_json::Value>,
+
+    /// Ids of the books with each ISBN, kept up to date by every mutation.
+    #[serde(skip)]
+    isbn_index: HashMap<String, HashSet<Uuid>>,
+
+    /// Gives new books their ids.
+    #[serde(skip)]
+    ids: IdGenerator,
+
+    /// Told about every change, if set with [`Library::set_observer`].
+    #[serde(skip)]
+    observer: Observer,
 }
 
 impl Library {
     #[must_use]
     pub fn new() -> Self {
-        Self { books: Vec::new() }
+        Self::default()
+    }
+
+    /// Creates a library holding `books`, giving a new id to any book without one or with the
+    /// same id as an earlier book.
+    #[must_use]
+    pub fn from_books(books: Vec<Book>) -> Self {
+        let mut library = Self {
+            books,
+            ..Self::default()
+        };
+        library.assign_missing_ids();
+        library.isbn_index = Self::build_isbn_index(&library.books);
+        library
+    }
+
+    /// Sets where the ids of books added from now on come from, e.g. a seeded generator so
+    /// tests get the same ids every run.
+    pub const fn set_id_generator(&mut self, ids: IdGenerator) {
+        self.ids = ids;
     }
 
-    /// Adds a new book to the library.
+    /// Adds a new book to the library, giving it a new id if it doesn't have one.
     pub fn add(&mut self, mut book: Book) {
         if book.id.is_nil() {
-            book.id = Uuid::new_v4();
+            book.id = self.new_id();
         }
+        self.index_isbn(book.isbn.as_ref(), book.id);
+        self.observer.emit(LibraryEvent::Added(book.id));
         self.books.push(book);
+        self.debug_assert_indexes();
+    }
+
+    /// Returns an id for a new book that no book in the library has.
+    fn new_id(&mut self) -> Uuid {
+        let mut id = self.ids.next_id();
+        while self.get(id).is_some() {
+            id = self.ids.next_id();
+        }
+        id
     }
 
     /// Removes a book from the library
-    pub fn remove(&mut self, id: Uuid) -> Result<(), io::Error> {
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no book with `id`.
+    pub fn remove(&mut self, id: Uuid) -> Result<(), SpineError> {
         let rm_idx = self.get_index(id)?;
-        self.books.remove(rm_idx);
+        let removed = self.books.remove(rm_idx);
+        self.unindex_isbn(removed.isbn.as_ref(), id);
+        self.observer.emit(LibraryEvent::Removed(id));
+        self.debug_assert_indexes();
+
+        Ok(())
+    }
+
+    /// Keeps only the books `f` returns true for, in their order, returning how many were
+    /// removed.
+    pub fn retain(&mut self, mut f: impl FnMut(&Book) -> bool) -> usize {
+        let mut removed = Vec::new();
+        self.books.retain(|b| {
+            let keep = f(b);
+            if !keep {
+                removed.push(b.id);
+            }
+            keep
+        });
+        if !removed.is_empty() {
+            self.isbn_index = Self::build_isbn_index(&self.books);
+        }
+        for &id in &removed {
+            self.observer.emit(LibraryEvent::Removed(id));
+        }
+        self.debug_assert_indexes();
+        removed.len()
+    }
+
+    /// Changes the title, author, ISBN or notes of a book, leaving any fields that are `None` as
+    /// they are. Blank notes clear the book's notes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no book with `id`.
+    pub fn update_fields(&mut self, id: Uuid, update: BookUpdate) -> Result<(), SpineError> {
+        let book = self.get_mut(id).ok_or(SpineError::NotFound)?;
+        if let Some(title) = update.title {
+            book.title = title;
+        }
+        if let Some(author) = update.author {
+            book.author = author;
+        }
+        if let Some(notes) = update.notes {
+            book.notes = Some(notes.trim().to_owned()).filter(|n| !n.is_empty());
+        }
+        if let Some(isbn) = update.isbn {
+            let old = book.isbn.replace(isbn.clone());
+            self.unindex_isbn(old.as_ref(), id);
+            self.index_isbn(Some(&isbn), id);
+        }
+        self.observer.emit(LibraryEvent::Updated(id));
+        self.debug_assert_indexes();
 
         Ok(())
     }
 
-    /// Updates status of a book in the library.
-    pub fn update_status(&mut self, id: Uuid, new_status: Status) -> Result<(), io::Error> {
-        let update_idx = self.get_index(id)?;
-        self.books[update_idx].status = new_status;
+    /// Updates status of a book in the library, with the side effects of [`Book::set_status`]
+    /// as of the clock's date.
+    ///
+    /// Returns whether the status changed, leaving the book untouched if it already had it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no book with `id`.
+    pub fn update_status(
+        &mut self,
+        id: Uuid,
+        new_status: Status,
+        clock: &dyn Clock,
+    ) -> Result<bool, SpineError> {
+        let book = self.get_mut(id).ok_or(SpineError::NotFound)?;
+        let from = book.status;
+        if from == new_status {
+            return Ok(false);
+        }
+        book.set_status(new_status, clock.today());
+        self.observer.emit(LibraryEvent::StatusChanged {
+            id,
+            from,
+            to: new_status,
+        });
+        self.debug_assert_indexes();
+
+        Ok(true)
+    }
+
+    /// Counts another read of a book, marking it read and finished today even if it already was.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no book with `id`.
+    pub fn reread(&mut self, id: Uuid, clock: &dyn Clock) -> Result<(), SpineError> {
+        let book = self.get_mut(id).ok_or(SpineError::NotFound)?;
+        let from = book.status;
+        book.set_status(Status::Read, clock.today());
+        if from == Status::Read {
+            // Not a change of status, so counted here rather than by `set_status`.
+            book.date_finished = Some(clock.today());
+            book.times_read += 1;
+        }
+        self.observer.emit(if from == Status::Read {
+            LibraryEvent::Updated(id)
+        } else {
+            LibraryEvent::StatusChanged {
+                id,
+                from,
+                to: Status::Read,
+            }
+        });
+        self.debug_assert_indexes();
 
         Ok(())
     }
 
-    pub fn tag<I>(&mut self, id: Uuid, tags: I) -> Result<(), io::Error>
+    /// Adds a copy of a book, e.g. another edition, as a new want-to-read book added as of the
+    /// clock's date, with the changes in `update` made to it, returning the copy's id.
+    ///
+    /// The copy keeps the book's other details but not its reading history: status, start and
+    /// finish dates, reads, rating and page are reset, and it is never archived.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`SpineError::NotFound`] if there is no book with `id`, or with
+    /// [`SpineError::Validation`] if the changed copy isn't valid, leaving the library as it was.
+    pub fn duplicate(
+        &mut self,
+        id: Uuid,
+        update: BookUpdate,
+        clock: &dyn Clock,
+    ) -> Result<Uuid, SpineError> {
+        let original = self.get(id).ok_or(SpineError::NotFound)?.clone();
+        let mut copy = Book {
+            status: Status::default(),
+            date_added: Some(clock.today()),
+            date_started: None,
+            date_finished: None,
+            times_read: 0,
+            rating: None,
+            page: None,
+            archived: false,
+            ..original
+        };
+        if let Some(title) = update.title {
+            copy.title = title;
+        }
+        if let Some(author) = update.author {
+            copy.author = author;
+        }
+        if let Some(isbn) = update.isbn {
+            copy.isbn = Some(isbn);
+        }
+        if let Some(notes) = update.notes {
+            copy.notes = Some(notes.trim().to_owned()).filter(|n| !n.is_empty());
+        }
+        copy.validate()?;
+        copy.id = self.new_id();
+        let copy_id = copy.id;
+        self.add(copy);
+
+        Ok(copy_id)
+    }
+
+    /// Merges the books of `other` into the library, adding books it doesn't have yet.
+    ///
+    /// A book already in the library is matched by ISBN, or else by title and author ignoring
+    /// case. If the two have different statuses, `policy` decides which to keep, calling `ask`
+    /// with the existing and incoming book for [`ConflictPolicy::Ask`] to choose whether to take
+    /// the incoming status.
+    ///
+    /// A book taking the incoming status gets the side effects of [`Book::set_status`] as of the
+    /// clock's date, then the start and finish dates of the incoming book where it has them and
+    /// its count of reads if higher.
+    pub fn merge(
+        &mut self,
+        other: Self,
+        policy: ConflictPolicy,
+        clock: &dyn Clock,
+        mut ask: impl FnMut(&Book, &Book) -> bool,
+    ) -> MergeSummary {
+        let mut summary = MergeSummary::default();
+        for mut incoming in other.books {
+            let Some(existing) = self.find_existing(&incoming) else {
+                incoming.id = Uuid::nil();
+                self.add(incoming);
+                summary.added.extend(self.books.last().map(|b| b.id));
+                continue;
+            };
+            if existing.status == incoming.status {
+                continue;
+            }
+            let take_incoming = match policy {
+                ConflictPolicy::Keep => false,
+                ConflictPolicy::Incoming => true,
+                ConflictPolicy::Furthest => incoming.status > existing.status,
+                ConflictPolicy::Ask => ask(existing, &incoming),
+            };
+            if take_incoming {
+                let (id, from) = (existing.id, existing.status);
+                if let Some(book) = self.get_mut(id) {
+                    book.set_status(incoming.status, clock.today());
+                    book.date_started = incoming.date_started.or(book.date_started);
+                    book.date_finished = incoming.date_finished.or(book.date_finished);
+                    book.times_read = book.times_read.max(incoming.times_read);
+                }
+                self.observer.emit(LibraryEvent::StatusChanged {
+                    id,
+                    from,
+                    to: incoming.status,
+                });
+                summary.updated.push(id);
+            }
+        }
+        self.debug_assert_indexes();
+
+        summary
+    }
+
+    /// Compares the library with `other`, matching books as [`Library::merge`] does.
+    ///
+    /// Matched books count as changed if any of their fields other than the id differ.
+    #[must_use]
+    pub fn diff<'a>(&'a self, other: &'a Self) -> LibraryDiff<'a> {
+        let mut diff = LibraryDiff::default();
+        let mut matched = HashSet::new();
+        for theirs in &other.books {
+            let Some(ours) = self.find_existing(theirs) else {
+                diff.only_in_other.push(theirs);
+                continue;
+            };
+            matched.insert(ours.id);
+            let theirs_with_our_id = Book {
+                id: ours.id,
+                ..theirs.clone()
+            };
+            if *ours != theirs_with_our_id {
+                diff.changed.push((ours, theirs));
+            }
+        }
+        diff.only_in_self = self
+            .books
+            .iter()
+            .filter(|b| !matched.contains(&b.id))
+            .collect();
+
+        diff
+    }
+
+    /// Finds the book in the library that `book` is another copy of.
+    #[must_use]
+    pub fn find_existing(&self, book: &Book) -> Option<&Book> {
+        if let Some(isbn) = &book.isbn
+            && let Some(found) = self.find_by_isbn(isbn.as_str()).next()
+        {
+            return Some(found);
+        }
+        let title = book.title.to_lowercase();
+        let author = book.author.to_string().to_lowercase();
+        self.books.iter().find(|b| {
+            b.title.to_lowercase() == title && b.author.to_string().to_lowercase() == author
+        })
+    }
+
+    /// Archives a book, or moves it back out of the archive, returning whether it changed.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no book with `id`.
+    pub fn set_archived(&mut self, id: Uuid, archived: bool) -> Result<bool, SpineError> {
+        let book = self.get_mut(id).ok_or(SpineError::NotFound)?;
+        let changed = book.archived != archived;
+        book.archived = archived;
+        if changed {
+            self.observer.emit(LibraryEvent::Updated(id));
+        }
+
+        Ok(changed)
+    }
+
+    /// Adds tags to a book with [`Book::add_tag`], returning whether any were new.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no book with `id`.
+    pub fn tag<I>(&mut self, id: Uuid, tags: I) -> Result<bool, SpineError>
     where
         I: IntoIterator<Item = String>,
     {
-        let tag_idx = self.get_index(id)?;
-        self.books[tag_idx].tags.extend(tags);
+        let book = self.get_mut(id).ok_or(SpineError::NotFound)?;
+        let mut changed = false;
+        for tag in tags {
+            changed |= book.add_tag(&tag);
+        }
+        if changed {
+            self.observer.emit(LibraryEvent::Updated(id));
+        }
 
-        Ok(())
+        Ok(changed)
     }
 
-    pub fn untag(&mut self, id: Uuid, tags: &Vec<String>) -> Result<(), io::Error> {
-        let tag_idx = self.get_index(id)?;
-        self.books[tag_idx].tags.retain(|t| !tags.contains(t));
+    /// Removes tags from a book with [`Book::remove_tag`], returning whether it had any of them.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no book with `id`.
+    pub fn untag(&mut self, id: Uuid, tags: &[String]) -> Result<bool, SpineError> {
+        let book = self.get_mut(id).ok_or(SpineError::NotFound)?;
+        let mut changed = false;
+        for tag in tags {
+            changed |= book.remove_tag(tag);
+        }
+        if changed {
+            self.observer.emit(LibraryEvent::Updated(id));
+        }
 
-        Ok(())
+        Ok(changed)
     }
 
-    fn get_index(&self, id: Uuid) -> Result<usize, io::Error> {
+    /// Returns the books with exactly this ISBN, ignoring any spaces or hyphens.
+    pub fn find_by_isbn(&self, isbn: &str) -> impl Iterator<Item = &Book> {
+        let ids = self.isbn_index.get(&Isbn::normalise(isbn));
         self.books
             .iter()
-            .position(|b| b.id == id)
-            .ok_or_else(|| io::Error::other("No books found."))
+            .filter(move |b| ids.is_some_and(|ids| ids.contains(&b.id)))
+    }
+
+    fn index_isbn(&mut self, isbn: Option<&Isbn>, id: Uuid) {
+        if let Some(isbn) = isbn {
+            self.isbn_index
+                .entry(isbn.as_str().to_owned())
+                .or_default()
+                .insert(id);
+        }
+    }
+
+    fn unindex_isbn(&mut self, isbn: Option<&Isbn>, id: Uuid) {
+        if let Some(isbn) = isbn
+            && let Some(ids) = self.isbn_index.get_mut(isbn.as_str())
+        {
+            ids.remove(&id);
+            if ids.is_empty() {
+                self.isbn_index.remove(isbn.as_str());
+            }
+        }
+    }
+
+    fn build_isbn_index(books: &[Book]) -> HashMap<String, HashSet<Uuid>> {
+        let mut index: HashMap<String, HashSet<Uuid>> = HashMap::new();
+        for book in books {
+            if let Some(isbn) = &book.isbn {
+                index
+                    .entry(isbn.as_str().to_owned())
+                    .or_default()
+                    .insert(book.id);
+            }
+        }
+        index
+    }
+
+    /// Checks in debug builds that the incrementally updated indexes match a full rebuild.
+    fn debug_assert_indexes(&self) {
+        debug_assert_eq!(
+            self.isbn_index,
+            Self::build_isbn_index(&self.books),
+            "ISBN index out of date"
+        );
     }
 
-    /// Searches library for books.
+    /// Returns the book with this id, if it is in the library.
     #[must_use]
+    pub fn get(&self, id: Uuid) -> Option<&Book> {
+        self.books.iter().find(|b| b.id == id)
+    }
+
+    /// Returns the book with this id for editing, if it is in the library.
+    ///
+    /// Kept within the crate, as changing a book's id or ISBN this way would leave the ISBN index
+    /// stale, and the change isn't reported to the observer. Callers outside use the targeted
+    /// mutators such as [`Library::update_fields`] instead.
+    pub(crate) fn get_mut(&mut self, id: Uuid) -> Option<&mut Book> {
+        self.books.iter_mut().find(|b| b.id == id)
+    }
+
+    fn get_index(&self, id: Uuid) -> Result<usize, SpineError> {
+        self.books
+            .iter()
+            .position(|b| b.id == id)
+            .ok_or(SpineError::NotFound)
+    }
+
+    /// Searches library for books, ignoring case and any whitespace around the criteria.
     pub fn search(&self, search: &LibrarySearch) -> impl Iterator<Item = &Book> {
-        self.books.iter().filter(|&b| {
-            search.title.as_ref().is_none_or(|t| b.title.contains(t))
-                && search
-                    .author
+        let title = search.title.as_deref().map(normalise_query);
+        let author = search.author.as_deref().map(normalise_query);
+        let text = search.text.as_deref().map(normalise_query);
+        let location = search.location.as_deref().map(normalise_query);
+        let tags: Option<Vec<String>> = search
+            .tags
+            .as_ref()
+            .map(|ts| ts.iter().map(|t| normalise_query(t)).collect());
+        self.books.iter().filter(move |&b| {
+            title
+                .as_ref()
+                .is_none_or(|t| b.title.to_lowercase().contains(t))
+                && author
                     .as_ref()
-                    .is_none_or(|a| b.author.to_string().contains(a))
+                    .is_none_or(|a| b.author.to_string().to_lowercase().contains(a))
                 && search
                     .isbn
                     .as_ref()
-                    .is_none_or(|c| b.isbn.as_ref().is_some_and(|i| i.as_str().contains(c)))
-                && search.status.is_none_or(|s| b.status == s)
+                    .is_none_or(|c| b.isbn.as_ref().is_some_and(|i| search.isbn_matches(i, c)))
                 && search
-                    .tags
+                    .status
                     .as_ref()
-                    .is_none_or(|ts| ts.iter().all(|t| b.tags.contains(t)))
+                    .is_none_or(|ss| ss.contains(&b.status))
+                && tags.as_ref().is_none_or(|ts| {
+                    ts.iter()
+                        .all(|t| b.tags.iter().any(|bt| bt.to_lowercase() == *t))
+                })
+                && b.archived == search.archived
+                && search.language.as_ref().is_none_or(|l| {
+                    b.language
+                        .as_ref()
+                        .is_some_and(|bl| bl.eq_ignore_ascii_case(l))
+                })
+                && location.as_ref().is_none_or(|l| {
+                    b.location
+                        .as_ref()
+                        .is_some_and(|bl| bl.to_lowercase().contains(l))
+                })
+                && text.as_ref().is_none_or(|t| contains_text(b, t))
+                && in_date_range(b.date_added, search.added_since, search.added_until)
+                && in_date_range(
+                    b.date_finished,
+                    search.finished_since,
+                    search.finished_until,
+                )
         })
     }
 
+    /// Returns the books whose title is close enough to `query` by `config`, closest first.
+    ///
+    /// Case and surrounding whitespace are ignored.
+    #[must_use]
+    pub fn fuzzy_search(&self, query: &str, config: FuzzyConfig) -> Vec<&Book> {
+        self.fuzzy_search_scored(query, config)
+            .into_iter()
+            .map(|(book, _)| book)
+            .collect()
+    }
+
+    /// Like [`Library::fuzzy_search`], but pairs each book with its score against `query`: the
+    /// edit distance for Levenshtein, or the similarity percentage for Jaro-Winkler. Books
+    /// equally close keep their library order.
+    #[must_use]
+    pub fn fuzzy_search_scored(&self, query: &str, config: FuzzyConfig) -> Vec<(&Book, usize)> {
+        let query = normalise_query(query);
+        let mut hits: Vec<_> = self
+            .books
+            .iter()
+            .map(|b| (b, config.algorithm.score(&query, &b.title.to_lowercase())))
+            .filter(|&(_, score)| config.accepts(score))
+            .collect();
+        match config.algorithm {
+            FuzzyAlgorithm::Levenshtein => hits.sort_by_key(|&(_, distance)| distance),
+            FuzzyAlgorithm::JaroWinkler => hits.sort_by_key(|&(_, similarity)| Reverse(similarity)),
+        }
+        hits
+    }
+
+    /// Returns copies of the books matching `search`, for callers that need results independent
+    /// of the library, e.g. to send to another thread.
+    ///
+    /// Every match is cloned, tags and all, so prefer [`Library::search`] where borrowing will do.
+    #[must_use]
+    pub fn search_owned(&self, search: &LibrarySearch) -> Vec<Book> {
+        self.search(search).cloned().collect()
+    }
+
+    /// Finds an author already in the library with the same name as `author` but different
+    /// casing.
+    #[must_use]
+    pub fn author_with_other_casing(&self, author: &Author) -> Option<&Author> {
+        let name = author.to_string();
+        self.books
+            .iter()
+            .map(|b| &b.author)
+            .find(|a| *a != author && a.to_string().to_lowercase() == name.to_lowercase())
+    }
+
+    /// Returns the number of books to finish each year, if set.
+    #[must_use]
+    pub const fn goal(&self) -> Option<u32> {
+        self.goal
+    }
+
+    /// Sets the number of books to finish each year, or clears it with `None`.
+    pub const fn set_goal(&mut self, goal: Option<u32>) {
+        self.goal = goal;
+    }
+
+    /// Returns when the library was first saved, if it has been.
+    #[must_use]
+    pub const fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    /// Returns when the library was last saved, if it has been.
+    #[must_use]
+    pub const fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    /// Compares the books finished in `year` against the reading goal, if one is set.
+    #[must_use]
+    pub fn goal_progress(&self, year: i32) -> Option<GoalProgress> {
+        let goal = self.goal?;
+        let finished = self
+            .books
+            .iter()
+            .filter(|b| b.date_finished.is_some_and(|d| d.year() == year))
+            .count();
+
+        Some(GoalProgress { finished, goal })
+    }
+
+    /// Counts the weeks in a row, up to the week of `today`, in which at least one book was
+    /// finished. Weeks run Monday to Sunday.
+    ///
+    /// A streak still counts if no book has been finished yet this week, as long as one was
+    /// finished last week; it is broken by a whole week with nothing finished. Without any
+    /// finish dates the streak is 0.
+    #[must_use]
+    pub fn reading_streak(&self, today: NaiveDate) -> u32 {
+        let week_start = |date: NaiveDate| date.week(Weekday::Mon).first_day();
+        let finished_weeks: HashSet<NaiveDate> = self
+            .books
+            .iter()
+            .filter_map(|b| b.date_finished)
+            .filter(|&d| d <= today)
+            .map(week_start)
+            .collect();
+        let this_week = week_start(today);
+        let mut week = if finished_weeks.contains(&this_week) {
+            this_week
+        } else {
+            this_week - Days::new(7)
+        };
+        let mut streak = 0;
+        while finished_weeks.contains(&week) {
+            streak += 1;
+            week = week - Days::new(7);
+        }
+        streak
+    }
+
+    /// Returns a summary of every book in the library.
+    #[must_use]
+    pub fn summaries(&self) -> Vec<BookSummary> {
+        self.books.iter().map(BookSummary::from).collect()
+    }
+
     /// Returns an iterator over all books in the library.
     pub fn all(&self) -> slice::Iter<'_, Book> {
         self.books.iter()
     }
 
-    /// Saves the library to a file.
-    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
-        let file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(path)?;
-        let buf = BufWriter::new(file);
-        serde_json::to_writer(buf, self)?;
+    /// Returns an iterator over the books with `status`, in the order they were added.
+    pub fn by_status(&self, status: Status) -> impl Iterator<Item = &Book> {
+        self.books.iter().filter(move |b| b.status == status)
+    }
 
-        Ok(())
+    /// Returns an iterator over all books in the library, most recently added first.
+    pub fn all_rev(&self) -> Rev<slice::Iter<'_, Book>> {
+        self.books.iter().rev()
+    }
+
+    /// Saves the library to a file, recording now as when it was updated, and created if it
+    /// hasn't been saved before. The file is only replaced once the library is written in full.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`SpineError::Changed`] rather than overwrite a file saved since this library
+    /// was loaded from it; use [`Library::save_forced`] to overwrite it anyway.
+    pub fn save(&mut self, path: impl AsRef<Path>) -> Result<(), SpineError> {
+        if Self::saved_updated_at(path.as_ref())? > self.updated_at {
+            return Err(SpineError::Changed);
+        }
+        self.save_forced(path)
+    }
+
+    /// Saves the library to a file like [`Library::save`], even if the file has been saved since
+    /// this library was loaded from it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be written.
+    pub fn save_forced(&mut self, path: impl AsRef<Path>) -> Result<(), SpineError> {
+        let unsaved = (self.created_at, self.updated_at);
+        self.stamp_saved();
+        let result = export::write_atomically(path.as_ref(), |writer| {
+            Ok(serde_json::to_writer(writer, &*self)?)
+        });
+        // A failed save leaves the file as it was, so the library must still look unsaved.
+        if result.is_err() {
+            (self.created_at, self.updated_at) = unsaved;
+        }
+
+        result
     }
 
     /// Opens the library from a file.
-    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+    ///
+    /// Books with a missing or duplicate id, e.g. from files saved before ids existed, are given
+    /// new ids, which the next save keeps. Files saved before creation times were recorded are
+    /// taken to have been created when the file was last modified.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or isn't a valid library.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SpineError> {
+        Self::open_with_repairs(path).map(|(library, _)| library)
+    }
+
+    /// Opens the library from a file like [`Library::open`], also returning how many books were
+    /// given new ids.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or isn't a valid library.
+    pub fn open_with_repairs(path: impl AsRef<Path>) -> Result<(Self, usize), SpineError> {
         let file = File::open(path)?;
-        let buf = BufReader::new(file);
-        let deserialized: Self = serde_json::from_reader(buf)?;
+        let modified = file.metadata()?.modified().ok();
+        Self::from_reader(BufReader::new(file), modified)
+    }
+
+    /// Records now as when the library was updated, and created if it hasn't been saved before.
+    fn stamp_saved(&mut self) {
+        let now = Utc::now();
+        self.created_at.get_or_insert(now);
+        self.updated_at = Some(now);
+    }
+
+    /// Reads a library saved as JSON, fixing it up like [`Library::open`] and returning how many
+    /// books were given new ids. `modified` is when its file was last modified, if known.
+    fn from_reader(
+        reader: impl Read,
+        modified: Option<SystemTime>,
+    ) -> Result<(Self, usize), SpineError> {
+        let mut deserialized: Self = serde_json::from_reader(reader)?;
+        if deserialized.created_at.is_none() {
+            deserialized.created_at = modified.map(DateTime::from);
+        }
+        let fixed = deserialized.assign_missing_ids();
+        deserialized.isbn_index = Self::build_isbn_index(&deserialized.books);
+
+        Ok((deserialized, fixed))
+    }
+
+    /// Returns when the library file at `path` was last saved, or `None` if it doesn't exist or
+    /// predates update times being recorded.
+    fn saved_updated_at(path: &Path) -> Result<Option<DateTime<Utc>>, SpineError> {
+        match File::open(path) {
+            Ok(file) => Self::read_updated_at(BufReader::new(file)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Reads when a library saved as JSON was last saved, without reading its books.
+    fn read_updated_at(reader: impl Read) -> Result<Option<DateTime<Utc>>, SpineError> {
+        #[derive(Deserialize)]
+        struct Saved {
+            #[serde(default)]
+            updated_at: Option<DateTime<Utc>>,
+        }
+
+        let saved: Saved = serde_json::from_reader(reader)?;
+        Ok(saved.updated_at)
+    }
+
+    /// Checks every book with [`Book::validate`], returning each invalid book with why it failed.
+    #[must_use]
+    pub fn validate_all(&self) -> Vec<(&Book, SpineError)> {
+        self.books
+            .iter()
+            .filter_map(|b| b.validate().err().map(|e| (b, e)))
+            .collect()
+    }
+
+    /// Cleans up each book with [`Book::normalise`], returning how many changed.
+    pub fn normalise(&mut self) -> usize {
+        let mut changed = 0;
+        for book in &mut self.books {
+            if book.normalise() {
+                changed += 1;
+                self.observer.emit(LibraryEvent::Updated(book.id));
+            }
+        }
+        self.isbn_index = Self::build_isbn_index(&self.books);
+        changed
+    }
+
+    /// Gives each book with a nil id, or an id an earlier book already has, a new id, returning
+    /// how many were changed.
+    fn assign_missing_ids(&mut self) -> usize {
+        let mut seen = HashSet::new();
+        let mut fixed = 0;
+        for book in &mut self.books {
+            if book.id.is_nil() || !seen.insert(book.id) {
+                book.id = self.ids.next_id();
+                while !seen.insert(book.id) {
+                    book.id = self.ids.next_id();
+                }
+                fixed += 1;
+            }
+        }
+        fixed
+    }
+
+    /// Opens the library from a file, or returns `None` if the file doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Library::open`] if the file exists.
+    pub fn open_optional(path: impl AsRef<Path>) -> Result<Option<Self>, SpineError> {
+        Ok(Self::open_optional_with_repairs(path)?.map(|(library, _)| library))
+    }
 
-        Ok(deserialized)
+    /// Opens the library from a file like [`Library::open_optional`], also returning how many
+    /// books were given new ids.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Library::open`] if the file exists.
+    pub fn open_optional_with_repairs(
+        path: impl AsRef<Path>,
+    ) -> Result<Option<(Self, usize)>, SpineError> {
+        match Self::open_with_repairs(path) {
+            Ok(opened) => Ok(Some(opened)),
+            Err(SpineError::Io(e)) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
     }
 }
 
@@ -127,17 +885,303 @@ pub struct LibrarySearch {
     pub title: Option<String>,
     pub author: Option<String>,
     pub isbn: Option<String>,
-    pub status: Option<Status>,
+    /// Match books whose ISBN ends with `isbn`, rather than contains it anywhere.
+    pub isbn_suffix: bool,
+    /// Match books with any of these statuses.
+    pub status: Option<Vec<Status>>,
     pub tags: Option<Vec<String>>,
+    /// Match books with this in their title, author, notes or any tag.
+    pub text: Option<String>,
+    /// Match books added on or after this date.
+    pub added_since: Option<NaiveDate>,
+    /// Match books added on or before this date.
+    pub added_until: Option<NaiveDate>,
+    /// Match books finished on or after this date.
+    pub finished_since: Option<NaiveDate>,
+    /// Match books finished on or before this date.
+    pub finished_until: Option<NaiveDate>,
+    /// ISO 639-1 code of the language to match, e.g. "en".
+    pub language: Option<String>,
+    /// Match books whose location contains this, e.g. "living room".
+    pub location: Option<String>,
+    /// Match archived books instead of the rest.
+    pub archived: bool,
+}
+
+impl LibrarySearch {
+    fn isbn_matches(&self, isbn: &Isbn, query: &str) -> bool {
+        let query = Isbn::normalise(query);
+        if self.isbn_suffix {
+            isbn.as_str().ends_with(&query)
+        } else {
+            isbn.as_str().contains(&query)
+        }
+    }
+}
+
+/// Returns the only book in `hits`.
+///
+/// # Errors
+///
+/// Fails with [`SpineError::NotFound`] if there are none or [`SpineError::Ambiguous`] if there
+/// are several.
+pub fn resolve_single<'a>(hits: &[&'a Book]) -> Result<&'a Book, SpineError> {
+    match *hits {
+        [] => Err(SpineError::NotFound),
+        [book] => Ok(book),
+        _ => Err(SpineError::Ambiguous(hits.len())),
+    }
+}
+
+/// Trims and lowercases a search criterion, so it matches regardless of case or stray spaces.
+fn normalise_query(query: &str) -> String {
+    query.trim().to_lowercase()
+}
+
+/// Returns whether `date` is within the inclusive bounds, if any are given. Books without the date
+/// never match a bound.
+fn in_date_range(
+    date: Option<NaiveDate>,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+) -> bool {
+    if since.is_none() && until.is_none() {
+        return true;
+    }
+    date.is_some_and(|d| since.is_none_or(|s| d >= s) && until.is_none_or(|u| d <= u))
+}
+
+/// Counts the single-character insertions, deletions and substitutions needed to turn `a` into
+/// `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Scores how similar `a` and `b` are from 0 to 100, weighting a shared prefix of up to four
+/// characters more heavily. Computed in integers so the percentage is rounded exactly.
+fn jaro_winkler(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() || b.is_empty() {
+        return if a == b { 100 } else { 0 };
+    }
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut b_taken = vec![false; b.len()];
+    let mut a_matches = Vec::new();
+    for (i, &ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        if let Some(j) = (start..end).find(|&j| !b_taken[j] && b[j] == ca) {
+            b_taken[j] = true;
+            a_matches.push(ca);
+        }
+    }
+    let matches = a_matches.len();
+    if matches == 0 {
+        return 0;
+    }
+    let b_in_order = b
+        .iter()
+        .zip(&b_taken)
+        .filter(|&(_, &taken)| taken)
+        .map(|(c, _)| c);
+    let transpositions = a_matches
+        .iter()
+        .zip(b_in_order)
+        .filter(|&(ca, cb)| ca != cb)
+        .count()
+        / 2;
+
+    // Jaro similarity is the mean of matches / a.len(), matches / b.len() and
+    // (matches - transpositions) / matches, kept as numerator / denominator.
+    let lengths = a.len() * b.len();
+    let numerator = matches.pow(2) * (a.len() + b.len()) + (matches - transpositions) * lengths;
+    let denominator = 3 * matches * lengths;
+    let prefix = a
+        .iter()
+        .zip(&b)
+        .take(4)
+        .take_while(|(ca, cb)| ca == cb)
+        .count();
+    // Winkler's boost moves the similarity a tenth of the way to 1 for each prefix character.
+    let boosted = numerator * (10 - prefix) + prefix * denominator;
+    (10 * boosted + denominator / 2) / denominator
+}
+
+/// Returns whether `text`, already normalised, appears in any of the book's text fields.
+fn contains_text(book: &Book, text: &str) -> bool {
+    book.title.to_lowercase().contains(text)
+        || book.author.to_string().to_lowercase().contains(text)
+        || book
+            .notes
+            .as_ref()
+            .is_some_and(|n| n.to_lowercase().contains(text))
+        || book.tags.iter().any(|t| t.to_lowercase().contains(text))
+}
+
+/// Fields to change on a book with [`Library::update_fields`].
+#[derive(Clone, Debug, Default)]
+pub struct BookUpdate {
+    pub title: Option<String>,
+    pub author: Option<Author>,
+    pub isbn: Option<Isbn>,
+    pub notes: Option<String>,
+}
+
+/// How [`Library::fuzzy_search`] measures how close a title is to the query.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FuzzyAlgorithm {
+    /// Counts the edits needed to turn one into the other.
+    #[default]
+    Levenshtein,
+    /// Scores their similarity as a percentage, favouring titles that start like the query, which
+    /// suits short titles better.
+    JaroWinkler,
+}
+
+impl FuzzyAlgorithm {
+    fn score(self, query: &str, title: &str) -> usize {
+        match self {
+            Self::Levenshtein => edit_distance(query, title),
+            Self::JaroWinkler => jaro_winkler(query, title),
+        }
+    }
+}
+
+/// How [`Library::fuzzy_search`] matches titles.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FuzzyConfig {
+    pub algorithm: FuzzyAlgorithm,
+    /// The most edits allowed for [`FuzzyAlgorithm::Levenshtein`], or the lowest similarity
+    /// percentage allowed for [`FuzzyAlgorithm::JaroWinkler`].
+    pub threshold: usize,
+}
+
+impl FuzzyConfig {
+    /// Matches titles within `max_distance` edits of the query.
+    #[must_use]
+    pub const fn levenshtein(max_distance: usize) -> Self {
+        Self {
+            algorithm: FuzzyAlgorithm::Levenshtein,
+            threshold: max_distance,
+        }
+    }
+
+    /// Matches titles at least `min_similarity` percent similar to the query.
+    #[must_use]
+    pub const fn jaro_winkler(min_similarity: usize) -> Self {
+        Self {
+            algorithm: FuzzyAlgorithm::JaroWinkler,
+            threshold: min_similarity,
+        }
+    }
+
+    const fn accepts(self, score: usize) -> bool {
+        match self.algorithm {
+            FuzzyAlgorithm::Levenshtein => score <= self.threshold,
+            FuzzyAlgorithm::JaroWinkler => score >= self.threshold,
+        }
+    }
+}
+
+impl Default for FuzzyConfig {
+    fn default() -> Self {
+        Self::levenshtein(2)
+    }
+}
+
+/// How [`Library::merge`] settles a book that both libraries have with different statuses.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ConflictPolicy {
+    /// Keep the status already in the library.
+    Keep,
+    /// Take the status of the incoming book.
+    Incoming,
+    /// Take whichever status is further along.
+    #[default]
+    Furthest,
+    /// Ask which to take for each conflict.
+    Ask,
+}
+
+impl FromStr for ConflictPolicy {
+    type Err = SpineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "keep" => Ok(Self::Keep),
+            "incoming" => Ok(Self::Incoming),
+            "furthest" => Ok(Self::Furthest),
+            "ask" => Ok(Self::Ask),
+            _ => Err(SpineError::Validation(
+                "Invalid conflict policy: expected 'keep', 'incoming', 'furthest', or 'ask'".into(),
+            )),
+        }
+    }
+}
+
+/// Books added and updated by [`Library::merge`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MergeSummary {
+    pub added: Vec<Uuid>,
+    pub updated: Vec<Uuid>,
+}
+
+/// How two libraries differ, from [`Library::diff`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LibraryDiff<'a> {
+    /// Books in this library with no copy in the other.
+    pub only_in_self: Vec<&'a Book>,
+    /// Books in the other library with no copy in this one.
+    pub only_in_other: Vec<&'a Book>,
+    /// Books in both whose details differ, as this library's copy then the other's.
+    pub changed: Vec<(&'a Book, &'a Book)>,
+}
+
+/// Books finished in a year compared against the reading goal.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GoalProgress {
+    pub finished: usize,
+    pub goal: u32,
+}
+
+impl GoalProgress {
+    /// Returns the percentage of the goal reached, rounded down.
+    #[must_use]
+    pub const fn percent(&self) -> usize {
+        if self.goal == 0 {
+            return 100;
+        }
+        self.finished * 100 / self.goal as usize
+    }
+}
+
+impl Display for GoalProgress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} / {} ({}%)", self.finished, self.goal, self.percent())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::FixedClock;
     use std::{collections::HashSet, str::FromStr, sync::LazyLock};
     use tempfile::tempdir;
     use uuid::uuid;
 
+    const CLOCK: FixedClock = FixedClock(NaiveDate::from_ymd_opt(2025, 3, 9).unwrap());
+
     static BURMESE_DAYS: LazyLock<Book> = LazyLock::new(|| Book {
         id: uuid!("a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8"),
         title: "burmese days".to_owned(),
@@ -161,25 +1205,75 @@ mod tests {
     });
 
     fn library_with_two_books() -> Library {
-        let mut my_lib = Library::new();
-        my_lib.add(BURMESE_DAYS.clone());
-        my_lib.add(KIM.clone());
-        my_lib
+        Library::from_books(vec![BURMESE_DAYS.clone(), KIM.clone()])
     }
 
     #[test]
-    fn add_adds_new_book_without_isbn() {
-        let mut my_lib = Library::new();
+    fn all_rev_lists_books_newest_first() {
+        let mut my_lib = library_with_two_books();
+        my_lib.add(EIGHTY_DAYS.clone());
 
-        my_lib.add(BURMESE_DAYS.clone());
+        let reversed: Vec<_> = my_lib.all_rev().collect();
+        let mut expected: Vec<_> = my_lib.all().collect();
+        expected.reverse();
 
-        assert_eq!(my_lib.all().next().unwrap(), &*BURMESE_DAYS);
+        assert_eq!(reversed, expected);
+        assert_eq!(reversed.len(), 3);
     }
 
     #[test]
-    fn remove_removes_book_from_library() {
+    fn by_status_yields_books_of_status_in_insertion_order() {
         let mut my_lib = library_with_two_books();
-        let rm_id = BURMESE_DAYS.id;
+        my_lib.add(EIGHTY_DAYS.clone());
+
+        let want: Vec<_> = my_lib.by_status(Status::Want).collect();
+
+        assert_eq!(want, vec![&*BURMESE_DAYS, &*EIGHTY_DAYS]);
+        assert_eq!(
+            my_lib.by_status(Status::Read).collect::<Vec<_>>(),
+            vec![&*KIM]
+        );
+        assert_eq!(my_lib.by_status(Status::Reading).count(), 0);
+    }
+
+    #[test]
+    fn from_books_assigns_missing_ids_and_indexes_isbns() {
+        let my_lib = Library::from_books(vec![
+            BURMESE_DAYS.clone(),
+            Book {
+                id: Uuid::nil(),
+                ..KIM.clone()
+            },
+        ]);
+
+        assert_eq!(my_lib.all().count(), 2);
+        assert!(my_lib.all().all(|b| !b.id.is_nil()));
+        let by_isbn: Vec<_> = my_lib.find_by_isbn("9780199536467").collect();
+        assert_eq!(by_isbn.len(), 1);
+        assert_eq!(by_isbn[0].title, "kim");
+        let my_search = LibrarySearch {
+            title: Some("burmese".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            my_lib.search(&my_search).collect::<Vec<_>>(),
+            vec![&*BURMESE_DAYS]
+        );
+    }
+
+    #[test]
+    fn add_adds_new_book_without_isbn() {
+        let mut my_lib = Library::new();
+
+        my_lib.add(BURMESE_DAYS.clone());
+
+        assert_eq!(my_lib.all().next().unwrap(), &*BURMESE_DAYS);
+    }
+
+    #[test]
+    fn remove_removes_book_from_library() {
+        let mut my_lib = library_with_two_books();
+        let rm_id = BURMESE_DAYS.id;
 
         my_lib.remove(rm_id).unwrap();
 
@@ -196,27 +1290,136 @@ mod tests {
         assert!(err.to_string().contains("No books found."));
     }
 
+    #[test]
+    fn retain_keeps_only_matching_books_and_updates_isbn_index() {
+        let mut my_lib = library_with_two_books();
+        my_lib.add(Book {
+            isbn: KIM.isbn.clone(),
+            ..EIGHTY_DAYS.clone()
+        });
+
+        let removed = my_lib.retain(|b| b.status == Status::Read);
+
+        assert_eq!(removed, 2);
+        assert_eq!(my_lib.all().collect::<Vec<_>>(), [&*KIM]);
+        assert_eq!(my_lib.find_by_isbn("9780199536467").count(), 1);
+        assert_eq!(my_lib.isbn_index, Library::build_isbn_index(&my_lib.books));
+    }
+
     #[test]
     fn update_status_changes_book_status() {
         let mut my_lib = library_with_two_books();
         let expected = Book {
             status: Status::Reading,
+            date_started: Some(CLOCK.0),
             ..BURMESE_DAYS.clone()
         };
         let update_id = BURMESE_DAYS.id;
 
-        my_lib.update_status(update_id, Status::Reading).unwrap();
+        my_lib
+            .update_status(update_id, Status::Reading, &CLOCK)
+            .unwrap();
 
         assert_eq!(my_lib.all().next().unwrap(), &expected);
     }
 
+    #[test]
+    fn update_status_to_read_counts_a_read() {
+        let mut my_lib = library_with_two_books();
+
+        my_lib
+            .update_status(BURMESE_DAYS.id, Status::Read, &CLOCK)
+            .unwrap();
+
+        assert_eq!(my_lib.get(BURMESE_DAYS.id).unwrap().times_read, 1);
+    }
+
+    #[test]
+    fn update_status_to_read_does_not_recount_already_read_book() {
+        let mut my_lib = library_with_two_books();
+
+        my_lib.update_status(KIM.id, Status::Read, &CLOCK).unwrap();
+
+        assert_eq!(my_lib.get(KIM.id).unwrap().times_read, 0);
+    }
+
+    #[test]
+    fn update_status_to_same_status_is_no_change() {
+        let mut my_lib = library_with_two_books();
+
+        let changed = my_lib.update_status(KIM.id, Status::Read, &CLOCK).unwrap();
+
+        assert!(!changed);
+        assert_eq!(my_lib.get(KIM.id).unwrap(), &*KIM);
+    }
+
+    #[test]
+    fn reread_counts_a_read_of_already_read_book() {
+        let mut my_lib = library_with_two_books();
+
+        my_lib.reread(KIM.id, &CLOCK).unwrap();
+        my_lib.reread(KIM.id, &CLOCK).unwrap();
+
+        assert_eq!(my_lib.get(KIM.id).unwrap().times_read, 2);
+    }
+
+    #[test]
+    fn reread_of_unread_book_marks_it_read_once() {
+        let mut my_lib = library_with_two_books();
+
+        my_lib.reread(BURMESE_DAYS.id, &CLOCK).unwrap();
+
+        let book = my_lib.get(BURMESE_DAYS.id).unwrap();
+        assert_eq!(book.status, Status::Read);
+        assert_eq!(book.date_finished, Some(CLOCK.today()));
+        assert_eq!(book.times_read, 1);
+    }
+
+    #[test]
+    fn duplicate_adds_unread_copy_with_new_id() {
+        let mut my_lib = library_with_two_books();
+
+        let copy_id = my_lib
+            .duplicate(KIM.id, BookUpdate::default(), &CLOCK)
+            .unwrap();
+
+        let copy = my_lib.get(copy_id).unwrap();
+        assert_ne!(copy_id, KIM.id);
+        assert_eq!((&copy.title, &copy.author), (&KIM.title, &KIM.author));
+        assert_eq!(copy.status, Status::Want);
+        assert_eq!((copy.date_added, copy.date_finished), (Some(CLOCK.0), None));
+        assert_eq!(copy.times_read, 0);
+        assert_eq!(my_lib.get(KIM.id).unwrap(), &*KIM);
+        assert_eq!(
+            my_lib
+                .find_by_isbn(KIM.isbn.as_ref().unwrap().as_str())
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn duplicate_leaves_library_unchanged_if_copy_is_invalid() {
+        let mut my_lib = library_with_two_books();
+        let before = my_lib.clone();
+        let update = BookUpdate {
+            title: Some(" ".to_owned()),
+            ..Default::default()
+        };
+
+        let result = my_lib.duplicate(KIM.id, update, &CLOCK);
+
+        assert!(matches!(result, Err(SpineError::Validation(_))));
+        assert_eq!(my_lib, before);
+    }
+
     #[test]
     fn update_status_throws_error_if_id_not_present() {
         let mut my_lib = library_with_two_books();
         let update_id = uuid!("c1c2c3c4-d1d2-e1e2-f1f2-f3f4f5f6f7f8");
 
         let err = my_lib
-            .update_status(update_id, Status::Reading)
+            .update_status(update_id, Status::Reading, &CLOCK)
             .unwrap_err();
 
         assert!(err.to_string().contains("No books found."));
@@ -269,12 +1472,52 @@ mod tests {
         };
 
         my_lib
-            .untag(KIM.id, &vec!["1800s".into(), "illustrated".into()])
+            .untag(KIM.id, &["1800s".into(), "illustrated".into()])
             .unwrap();
 
         assert_eq!(my_lib.all().last().unwrap(), &expected);
     }
 
+    #[test]
+    fn tag_and_untag_report_whether_book_changed() {
+        let mut my_lib = library_with_two_books();
+
+        assert!(my_lib.tag(KIM.id, vec!["Spy".into()]).unwrap());
+        assert!(!my_lib.tag(KIM.id, vec!["spy ".into()]).unwrap());
+        assert!(my_lib.untag(KIM.id, &["SPY".into()]).unwrap());
+        assert!(!my_lib.untag(KIM.id, &["spy".into()]).unwrap());
+    }
+
+    #[test]
+    fn validate_all_returns_only_invalid_books() {
+        let untitled = Book {
+            title: " ".into(),
+            ..EIGHTY_DAYS.clone()
+        };
+        let my_lib = Library::from_books(vec![KIM.clone(), untitled.clone()]);
+
+        let invalid = my_lib.validate_all();
+
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].0, &untitled);
+        assert!(matches!(invalid[0].1, SpineError::Validation(_)));
+    }
+
+    #[test]
+    fn get_returns_book_with_present_id() {
+        let my_lib = library_with_two_books();
+
+        assert_eq!(my_lib.get(KIM.id), Some(&*KIM));
+    }
+
+    #[test]
+    fn get_returns_none_for_absent_id() {
+        let mut my_lib = library_with_two_books();
+
+        assert_eq!(my_lib.get(EIGHTY_DAYS.id), None);
+        assert_eq!(my_lib.get_mut(EIGHTY_DAYS.id), None);
+    }
+
     #[test]
     fn get_index_returns_correct_index() {
         let mut my_lib = library_with_two_books();
@@ -286,6 +1529,32 @@ mod tests {
         assert_eq!(index, 2);
     }
 
+    #[test]
+    fn author_with_other_casing_finds_existing_spelling() {
+        let my_lib = library_with_two_books();
+
+        let existing = my_lib.author_with_other_casing(&Author::from_str("George ORWELL").unwrap());
+        let same = my_lib.author_with_other_casing(&Author::from_str("george orwell").unwrap());
+
+        assert_eq!(existing, Some(&BURMESE_DAYS.author));
+        assert_eq!(same, None);
+    }
+
+    #[test]
+    fn update_fields_reindexes_changed_isbn() {
+        let mut my_lib = library_with_two_books();
+        let update = BookUpdate {
+            isbn: Some(Isbn::from_str("0-14-018474-6").unwrap()),
+            ..Default::default()
+        };
+
+        my_lib.update_fields(KIM.id, update).unwrap();
+
+        let new_hits: Vec<_> = my_lib.find_by_isbn("0140184746").map(|b| b.id).collect();
+        assert_eq!(new_hits, [KIM.id]);
+        assert_eq!(my_lib.find_by_isbn("9780199536467").count(), 0);
+    }
+
     #[test]
     fn search_finds_single_hit_by_title() {
         let my_lib = library_with_two_books();
@@ -349,11 +1618,41 @@ mod tests {
         assert_eq!(search_hits, vec![&*KIM]);
     }
 
+    #[test]
+    fn search_finds_isbn_by_last_digits_with_suffix() {
+        let my_lib = library_with_two_books();
+        let my_search = LibrarySearch {
+            isbn: Some("536-467".into()),
+            isbn_suffix: true,
+            ..Default::default()
+        };
+
+        let search_hits: Vec<_> = my_lib.search(&my_search).collect();
+
+        assert_eq!(search_hits, vec![&*KIM]);
+    }
+
+    #[test]
+    fn search_finds_isbn_by_middle_digits_only_without_suffix() {
+        let my_lib = library_with_two_books();
+        let mut my_search = LibrarySearch {
+            isbn: Some("199-536".into()),
+            ..Default::default()
+        };
+
+        let search_hits: Vec<_> = my_lib.search(&my_search).collect();
+        my_search.isbn_suffix = true;
+        let suffix_hits = my_lib.search(&my_search).count();
+
+        assert_eq!(search_hits, vec![&*KIM]);
+        assert_eq!(suffix_hits, 0);
+    }
+
     #[test]
     fn search_finds_single_hit_by_status() {
         let my_lib = library_with_two_books();
         let my_search = LibrarySearch {
-            status: Some(Status::Read),
+            status: Some(vec![Status::Read]),
             ..Default::default()
         };
 
@@ -362,6 +1661,23 @@ mod tests {
         assert_eq!(search_hits, vec![&*KIM]);
     }
 
+    #[test]
+    fn search_finds_hits_in_any_of_several_statuses() {
+        let mut my_lib = library_with_two_books();
+        my_lib.add(Book {
+            status: Status::Reading,
+            ..EIGHTY_DAYS.clone()
+        });
+        let my_search = LibrarySearch {
+            status: Some(vec![Status::Want, Status::Reading]),
+            ..Default::default()
+        };
+
+        let search_hits: Vec<_> = my_lib.search(&my_search).map(|b| b.id).collect();
+
+        assert_eq!(search_hits, vec![BURMESE_DAYS.id, EIGHTY_DAYS.id]);
+    }
+
     #[test]
     fn search_finds_single_hit_by_tags() {
         let my_lib = library_with_two_books();
@@ -375,6 +1691,21 @@ mod tests {
         assert_eq!(search_hits, vec![&*KIM]);
     }
 
+    #[test]
+    fn search_finds_hit_by_padded_title_of_different_case() {
+        let my_lib = library_with_two_books();
+        let my_search = LibrarySearch {
+            title: Some("  KIM ".into()),
+            author: Some(" Kipling".into()),
+            tags: Some(vec![" Classic ".into()]),
+            ..Default::default()
+        };
+
+        let search_hits: Vec<_> = my_lib.search(&my_search).collect();
+
+        assert_eq!(search_hits, vec![&*KIM]);
+    }
+
     #[test]
     fn search_finds_nothing_by_title() {
         let my_lib = library_with_two_books();
@@ -383,9 +1714,9 @@ mod tests {
             ..Default::default()
         };
 
-        let search_hits: Vec<_> = my_lib.search(&my_search).collect();
+        let mut search_hits = my_lib.search(&my_search);
 
-        assert!(search_hits.is_empty());
+        assert!(search_hits.next().is_none());
     }
 
     #[test]
@@ -396,33 +1727,763 @@ mod tests {
             ..Default::default()
         };
 
+        let mut search_hits = my_lib.search(&my_search);
+
+        assert!(search_hits.next().is_none());
+    }
+
+    #[test]
+    fn search_finds_text_only_in_notes() {
+        let mut my_lib = library_with_two_books();
+        let noted = Book {
+            id: uuid!("d1d2d3d4-e1e2-f1f2-a1a2-a3a4a5a6a7a8"),
+            title: "felix holt, the radical".to_owned(),
+            author: Author::from_str("george eliot").unwrap(),
+            notes: Some("Lent to Priya, ask for it back".to_owned()),
+            ..Default::default()
+        };
+        my_lib.add(noted.clone());
+        let my_search = LibrarySearch {
+            text: Some("priya".into()),
+            ..Default::default()
+        };
+
+        let search_hits: Vec<_> = my_lib.search(&my_search).collect();
+
+        assert_eq!(search_hits, vec![&noted]);
+    }
+
+    #[test]
+    fn search_finds_text_only_in_tags() {
+        let my_lib = library_with_two_books();
+        let my_search = LibrarySearch {
+            text: Some(" 1800S".into()),
+            ..Default::default()
+        };
+
+        let search_hits: Vec<_> = my_lib.search(&my_search).collect();
+
+        assert_eq!(search_hits, vec![&*KIM]);
+    }
+
+    #[test]
+    fn search_filters_by_part_of_location() {
+        let shelved = Book {
+            location: Some("Living room - shelf 2".into()),
+            ..EIGHTY_DAYS.clone()
+        };
+        let elsewhere = Book {
+            location: Some("Bedroom".into()),
+            ..KIM.clone()
+        };
+        let my_lib = Library::from_books(vec![BURMESE_DAYS.clone(), shelved.clone(), elsewhere]);
+        let my_search = LibrarySearch {
+            location: Some(" living ROOM ".into()),
+            ..Default::default()
+        };
+
         let search_hits: Vec<_> = my_lib.search(&my_search).collect();
 
-        assert!(search_hits.is_empty());
+        assert_eq!(search_hits, vec![&shelved]);
+    }
+
+    #[test]
+    fn search_filters_by_language() {
+        let french = Book {
+            language: Some("fr".into()),
+            ..EIGHTY_DAYS.clone()
+        };
+        let my_lib = Library::from_books(vec![BURMESE_DAYS.clone(), french.clone()]);
+        let my_search = LibrarySearch {
+            language: Some("FR".into()),
+            ..Default::default()
+        };
+
+        let search_hits: Vec<_> = my_lib.search(&my_search).collect();
+
+        assert_eq!(search_hits, vec![&french]);
+    }
+
+    #[test]
+    fn resolve_single_fails_without_hits() {
+        let err = resolve_single(&[]).unwrap_err();
+
+        assert!(matches!(err, SpineError::NotFound));
+    }
+
+    #[test]
+    fn resolve_single_returns_only_hit() {
+        let book = resolve_single(&[&*KIM]).unwrap();
+
+        assert_eq!(book, &*KIM);
+    }
+
+    #[test]
+    fn resolve_single_fails_with_several_hits() {
+        let err = resolve_single(&[&*KIM, &*BURMESE_DAYS]).unwrap_err();
+
+        assert!(matches!(err, SpineError::Ambiguous(2)));
+    }
+
+    #[test]
+    fn search_owned_equals_borrowed_results() {
+        let my_lib = library_with_two_books();
+        let my_search = LibrarySearch {
+            author: Some("kipling".into()),
+            ..Default::default()
+        };
+
+        let owned = my_lib.search_owned(&my_search);
+
+        let borrowed: Vec<_> = my_lib.search(&my_search).collect();
+        assert_eq!(owned.iter().collect::<Vec<_>>(), borrowed);
+        assert_eq!(owned, vec![KIM.clone()]);
+    }
+
+    #[test]
+    fn fuzzy_search_scored_ranks_typo_by_distance() {
+        let mut my_lib = library_with_two_books();
+        my_lib.add(EIGHTY_DAYS.clone());
+
+        let hits = my_lib.fuzzy_search_scored("burmse dayz", FuzzyConfig::levenshtein(20));
+
+        assert_eq!(hits[0], (&*BURMESE_DAYS, 2));
+        assert!(hits.windows(2).all(|w| w[0].1 <= w[1].1));
+    }
+
+    #[test]
+    fn fuzzy_search_drops_books_beyond_max_distance() {
+        let my_lib = library_with_two_books();
+
+        let hits = my_lib.fuzzy_search(" Kin ", FuzzyConfig::levenshtein(1));
+
+        assert_eq!(hits, vec![&*KIM]);
+    }
+
+    #[test]
+    fn fuzzy_search_algorithms_disagree_on_short_titles() {
+        let titles = ["Him", "Kimono"];
+        let my_lib = Library::from_books(
+            titles
+                .iter()
+                .map(|&title| Book {
+                    title: title.to_owned(),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+        let ranked = |config| -> Vec<_> {
+            my_lib
+                .fuzzy_search(" kim", config)
+                .iter()
+                .map(|b| b.title.as_str())
+                .collect()
+        };
+
+        assert_eq!(ranked(FuzzyConfig::levenshtein(3)), ["Him", "Kimono"]);
+        assert_eq!(ranked(FuzzyConfig::jaro_winkler(70)), ["Kimono", "Him"]);
+    }
+
+    #[test]
+    fn fuzzy_search_scored_gives_jaro_winkler_similarity() {
+        let my_lib = library_with_two_books();
+
+        let hits = my_lib.fuzzy_search_scored("burmese dyas", FuzzyConfig::jaro_winkler(90));
+
+        assert_eq!(hits, vec![(&*BURMESE_DAYS, 98)]);
+    }
+
+    #[test]
+    fn jaro_winkler_matches_reference_values() {
+        assert_eq!(jaro_winkler("martha", "marhta"), 96);
+        assert_eq!(jaro_winkler("dixon", "dicksonx"), 81);
+        assert_eq!(jaro_winkler("kim", "kim"), 100);
+        assert_eq!(jaro_winkler("kim", ""), 0);
+    }
+
+    #[test]
+    fn search_finds_books_added_since_date_inclusive() {
+        let mut my_lib = Library::new();
+        for (title, added) in [
+            ("burmese days", Some((2024, 2, 29))),
+            ("kim", Some((2024, 3, 1))),
+            ("animal farm", Some((2024, 3, 2))),
+            ("emma", None),
+        ] {
+            my_lib.add(Book {
+                title: title.to_owned(),
+                author: Author::from_str("some author").unwrap(),
+                date_added: added.and_then(|(y, m, d)| NaiveDate::from_ymd_opt(y, m, d)),
+                ..Default::default()
+            });
+        }
+        let my_search = LibrarySearch {
+            added_since: NaiveDate::from_ymd_opt(2024, 3, 1),
+            ..Default::default()
+        };
+
+        let titles: Vec<_> = my_lib
+            .search(&my_search)
+            .map(|b| b.title.as_str())
+            .collect();
+
+        assert_eq!(titles, vec!["kim", "animal farm"]);
+    }
+
+    #[test]
+    fn search_finds_books_finished_within_range() {
+        let mut my_lib = library_with_two_books();
+        my_lib.add(Book {
+            date_finished: NaiveDate::from_ymd_opt(2024, 6, 30),
+            ..EIGHTY_DAYS.clone()
+        });
+        let my_search = LibrarySearch {
+            finished_since: NaiveDate::from_ymd_opt(2024, 1, 1),
+            finished_until: NaiveDate::from_ymd_opt(2024, 6, 30),
+            ..Default::default()
+        };
+
+        let search_hits: Vec<_> = my_lib.search(&my_search).map(|b| b.id).collect();
+
+        assert_eq!(search_hits, vec![EIGHTY_DAYS.id]);
+    }
+
+    #[test]
+    fn search_hides_archived_book_unless_asked_for() {
+        let mut my_lib = library_with_two_books();
+        my_lib.set_archived(KIM.id, true).unwrap();
+
+        let active: Vec<_> = my_lib.search(&LibrarySearch::default()).collect();
+        let archived: Vec<_> = my_lib
+            .search(&LibrarySearch {
+                archived: true,
+                ..Default::default()
+            })
+            .map(|b| b.id)
+            .collect();
+
+        assert_eq!(active, vec![&*BURMESE_DAYS]);
+        assert_eq!(archived, vec![KIM.id]);
     }
 
     #[test]
     fn search_finds_all_by_nothing() {
         let my_lib = library_with_two_books();
 
+        let search_hits: Vec<_> = my_lib.search(&LibrarySearch::default()).collect();
+
+        assert_eq!(search_hits, my_lib.all().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn search_by_status_only_finds_every_book_with_that_status() {
+        let mut my_lib = library_with_two_books();
+        my_lib.add(Book {
+            status: Status::Read,
+            ..EIGHTY_DAYS.clone()
+        });
+
         let search_hits: Vec<_> = my_lib
             .search(&LibrarySearch {
+                status: Some(vec![Status::Read]),
                 ..Default::default()
             })
+            .map(|b| b.id)
             .collect();
 
-        assert_eq!(search_hits, my_lib.all().collect::<Vec<_>>());
+        assert_eq!(search_hits, vec![KIM.id, EIGHTY_DAYS.id]);
+    }
+
+    /// Merges copies of both books with different statuses, returning their statuses after.
+    fn merge_conflicting_statuses(policy: ConflictPolicy) -> (Status, Status) {
+        let mut my_lib = library_with_two_books();
+        let mut incoming = Library::new();
+        incoming.add(Book {
+            id: Uuid::nil(),
+            title: "Burmese Days".to_owned(),
+            status: Status::Reading,
+            ..BURMESE_DAYS.clone()
+        });
+        incoming.add(Book {
+            id: Uuid::nil(),
+            status: Status::Want,
+            ..KIM.clone()
+        });
+
+        let summary = my_lib.merge(incoming, policy, &CLOCK, |_, _| unreachable!());
+
+        assert!(summary.added.is_empty());
+        (
+            my_lib.get(BURMESE_DAYS.id).unwrap().status,
+            my_lib.get(KIM.id).unwrap().status,
+        )
     }
 
     #[test]
-    fn save_then_open_restores_library() {
+    fn merge_keep_leaves_existing_statuses() {
+        assert_eq!(
+            merge_conflicting_statuses(ConflictPolicy::Keep),
+            (Status::Want, Status::Read)
+        );
+    }
+
+    #[test]
+    fn merge_incoming_takes_incoming_statuses() {
+        assert_eq!(
+            merge_conflicting_statuses(ConflictPolicy::Incoming),
+            (Status::Reading, Status::Want)
+        );
+    }
+
+    #[test]
+    fn merge_furthest_takes_statuses_further_along() {
+        assert_eq!(
+            merge_conflicting_statuses(ConflictPolicy::Furthest),
+            (Status::Reading, Status::Read)
+        );
+    }
+
+    #[test]
+    fn merge_ask_takes_incoming_status_if_chosen() {
+        let mut my_lib = library_with_two_books();
+        let mut incoming = Library::new();
+        incoming.add(Book {
+            status: Status::Read,
+            ..BURMESE_DAYS.clone()
+        });
+        let mut asked = Vec::new();
+
+        my_lib.merge(
+            incoming,
+            ConflictPolicy::Ask,
+            &CLOCK,
+            |existing, incoming| {
+                asked.push((existing.status, incoming.status));
+                true
+            },
+        );
+
+        assert_eq!(asked, vec![(Status::Want, Status::Read)]);
+        assert_eq!(my_lib.get(BURMESE_DAYS.id).unwrap().status, Status::Read);
+    }
+
+    #[test]
+    fn merge_taking_read_status_counts_a_read_finished_today_if_undated() {
+        let mut my_lib = library_with_two_books();
+        let mut incoming = Library::new();
+        incoming.add(Book {
+            status: Status::Read,
+            ..BURMESE_DAYS.clone()
+        });
+
+        my_lib.merge(
+            incoming,
+            ConflictPolicy::Incoming,
+            &CLOCK,
+            |_, _| unreachable!(),
+        );
+
+        let book = my_lib.get(BURMESE_DAYS.id).unwrap();
+        assert_eq!(book.date_finished, Some(CLOCK.today()));
+        assert_eq!(book.times_read, 1);
+    }
+
+    #[test]
+    fn merge_matches_titles_ignoring_non_ascii_case() {
+        let mut my_lib = Library::new();
+        my_lib.add(Book {
+            title: "émile".to_owned(),
+            author: Author::from_str("jean-jacques rousseau").unwrap(),
+            ..Default::default()
+        });
+        let mut incoming = Library::new();
+        incoming.add(Book {
+            title: "ÉMILE".to_owned(),
+            author: Author::from_str("jean-jacques rousseau").unwrap(),
+            status: Status::Read,
+            times_read: 3,
+            ..Default::default()
+        });
+
+        let summary = my_lib.merge(
+            incoming,
+            ConflictPolicy::Incoming,
+            &CLOCK,
+            |_, _| unreachable!(),
+        );
+
+        assert!(summary.added.is_empty());
+        assert_eq!(my_lib.all().next().unwrap().times_read, 3);
+    }
+
+    #[test]
+    fn merge_adds_books_not_in_library() {
+        let mut my_lib = library_with_two_books();
+        let mut incoming = Library::new();
+        incoming.add(EIGHTY_DAYS.clone());
+
+        let summary = my_lib.merge(
+            incoming,
+            ConflictPolicy::default(),
+            &CLOCK,
+            |_, _| unreachable!(),
+        );
+
+        assert_eq!(summary.added.len(), 1);
+        assert_eq!(my_lib.all().count(), 3);
+    }
+
+    #[test]
+    fn diff_finds_books_only_in_self() {
+        let my_lib = library_with_two_books();
+        let other = Library::from_books(vec![KIM.clone()]);
+
+        let diff = my_lib.diff(&other);
+
+        assert_eq!(diff.only_in_self, vec![&*BURMESE_DAYS]);
+        assert!(diff.only_in_other.is_empty() && diff.changed.is_empty());
+    }
+
+    #[test]
+    fn diff_finds_books_only_in_other() {
+        let my_lib = library_with_two_books();
+        let mut other = library_with_two_books();
+        other.add(EIGHTY_DAYS.clone());
+
+        let diff = my_lib.diff(&other);
+
+        assert_eq!(diff.only_in_other, vec![&*EIGHTY_DAYS]);
+        assert!(diff.only_in_self.is_empty() && diff.changed.is_empty());
+    }
+
+    #[test]
+    fn diff_finds_matched_books_that_changed_ignoring_ids() {
+        let my_lib = library_with_two_books();
+        let reading = Book {
+            id: Uuid::new_v4(),
+            title: "Burmese Days".to_owned(),
+            status: Status::Reading,
+            ..BURMESE_DAYS.clone()
+        };
+        let kim_copy = Book {
+            id: Uuid::new_v4(),
+            ..KIM.clone()
+        };
+        let other = Library::from_books(vec![reading.clone(), kim_copy]);
+
+        let diff = my_lib.diff(&other);
+
+        assert_eq!(diff.changed, vec![(&*BURMESE_DAYS, &reading)]);
+        assert!(diff.only_in_self.is_empty() && diff.only_in_other.is_empty());
+    }
+
+    #[test]
+    fn update_status_to_read_sets_finish_date_from_clock() {
+        let mut my_lib = library_with_two_books();
+
+        my_lib
+            .update_status(BURMESE_DAYS.id, Status::Read, &CLOCK)
+            .unwrap();
+
+        let finished = my_lib.all().next().unwrap().date_finished;
+        assert_eq!(finished, Some(CLOCK.0));
+    }
+
+    #[test]
+    fn update_status_to_reading_sets_start_date_from_clock() {
+        let mut my_lib = library_with_two_books();
+
+        my_lib
+            .update_status(BURMESE_DAYS.id, Status::Reading, &CLOCK)
+            .unwrap();
+        let later = FixedClock(CLOCK.0 + Days::new(12));
+        my_lib
+            .update_status(BURMESE_DAYS.id, Status::Read, &later)
+            .unwrap();
+
+        let book = my_lib.get(BURMESE_DAYS.id).unwrap();
+        assert_eq!(book.date_started, Some(CLOCK.0));
+        assert_eq!(book.days_to_read(), Some(12));
+    }
+
+    #[test]
+    fn set_goal_is_kept_after_save_and_open() {
         let tmp_dir = tempdir().unwrap();
         let file_path = tmp_dir.path().join("my_library.json");
+        let mut my_lib = library_with_two_books();
+
+        my_lib.set_goal(Some(40));
+        my_lib.save(&file_path).unwrap();
+        let opened = Library::open(&file_path).unwrap();
+
+        assert_eq!(opened.goal(), Some(40));
+    }
+
+    #[test]
+    fn goal_progress_counts_books_finished_in_year() {
+        let mut my_lib = Library::new();
+        for (i, finished) in ["2024-12-31", "2025-01-01", "2025-06-15", "2025-11-30"]
+            .iter()
+            .enumerate()
+        {
+            my_lib.add(Book {
+                title: format!("book {i}"),
+                status: Status::Read,
+                date_finished: Some(finished.parse().unwrap()),
+                ..Default::default()
+            });
+        }
+        my_lib.add(EIGHTY_DAYS.clone());
+        my_lib.set_goal(Some(4));
+
+        let progress = my_lib.goal_progress(2025).unwrap();
+
+        assert_eq!(progress.finished, 3);
+        assert_eq!(progress.to_string(), "3 / 4 (75%)");
+    }
+
+    fn library_finished_on(dates: &[&str]) -> Library {
+        Library::from_books(
+            dates
+                .iter()
+                .map(|date| Book {
+                    status: Status::Read,
+                    date_finished: Some(date.parse().unwrap()),
+                    ..Default::default()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn reading_streak_counts_weeks_back_to_gap() {
+        // 2025-03-09 is a Sunday; nothing was finished in the week of 2025-02-17.
+        let my_lib = library_finished_on(&[
+            "2025-02-10",
+            "2025-02-24",
+            "2025-03-02",
+            "2025-03-03",
+            "2025-03-08",
+        ]);
+
+        assert_eq!(my_lib.reading_streak(CLOCK.0), 2);
+    }
+
+    #[test]
+    fn reading_streak_continues_from_last_week() {
+        let my_lib = library_finished_on(&["2025-02-28", "2025-03-07"]);
+
+        assert_eq!(my_lib.reading_streak(CLOCK.0 + Days::new(1)), 2);
+        assert_eq!(my_lib.reading_streak(CLOCK.0 + Days::new(8)), 0);
+    }
+
+    #[test]
+    fn reading_streak_is_zero_without_finish_dates() {
+        assert_eq!(library_with_two_books().reading_streak(CLOCK.0), 0);
+    }
+
+    #[test]
+    fn goal_progress_is_none_without_goal() {
         let my_lib = library_with_two_books();
 
+        assert_eq!(my_lib.goal_progress(2025), None);
+    }
+
+    #[test]
+    fn save_then_open_restores_library() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("my_library.json");
+        let mut my_lib = library_with_two_books();
+
         my_lib.save(&file_path).unwrap();
         let opened = Library::open(&file_path).unwrap();
 
         assert_eq!(opened, my_lib, "wrong data");
     }
+
+    #[test]
+    fn save_sets_created_and_updated_times() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("my_library.json");
+        let mut my_lib = library_with_two_books();
+
+        my_lib.save(&file_path).unwrap();
+
+        assert!(my_lib.created_at().is_some());
+        assert_eq!(my_lib.updated_at(), my_lib.created_at());
+    }
+
+    #[test]
+    fn save_again_advances_only_updated_time() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("my_library.json");
+        let mut my_lib = library_with_two_books();
+        my_lib.save(&file_path).unwrap();
+        let created = my_lib.created_at();
+        let first_update = my_lib.updated_at();
+
+        let mut opened = Library::open(&file_path).unwrap();
+        opened.save(&file_path).unwrap();
+
+        assert_eq!(opened.created_at(), created);
+        assert!(opened.updated_at() > first_update);
+    }
+
+    #[test]
+    fn failed_save_leaves_times_unchanged() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("missing").join("my_library.json");
+        let mut my_lib = library_with_two_books();
+
+        let err = my_lib.save(&file_path).unwrap_err();
+
+        assert!(matches!(err, SpineError::Io(_)));
+        assert_eq!(my_lib.created_at(), None);
+        assert_eq!(my_lib.updated_at(), None);
+    }
+
+    #[test]
+    fn save_refuses_to_overwrite_file_changed_since_open() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("my_library.json");
+        library_with_two_books().save(&file_path).unwrap();
+        let mut my_lib = Library::open(&file_path).unwrap();
+
+        let mut elsewhere = Library::open(&file_path).unwrap();
+        elsewhere.add(EIGHTY_DAYS.clone());
+        elsewhere.save(&file_path).unwrap();
+        let err = my_lib.save(&file_path).unwrap_err();
+
+        assert!(matches!(err, SpineError::Changed));
+        assert_eq!(Library::open(&file_path).unwrap().all().count(), 3);
+        my_lib.save_forced(&file_path).unwrap();
+        assert_eq!(Library::open(&file_path).unwrap().all().count(), 2);
+    }
+
+    #[test]
+    fn save_keeps_unknown_top_level_fields() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("spine.json");
+        std::fs::write(
+            &file_path,
+            r#"{"_comment": "my books", "books": [], "shelves": {"spare room": 2}}"#,
+        )
+        .unwrap();
+
+        let mut opened = Library::open(&file_path).unwrap();
+        opened.add(KIM.clone());
+        opened.save(&file_path).unwrap();
+
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&file_path).unwrap()).unwrap();
+        assert_eq!(saved["_comment"], "my books");
+        assert_eq!(saved["shelves"]["spare room"], 2);
+        assert_eq!(saved["books"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn open_backfills_created_time_for_old_file() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("spine.json");
+        std::fs::write(&file_path, r#"{"books": []}"#).unwrap();
+        let modified = std::fs::metadata(&file_path).unwrap().modified().unwrap();
+
+        let opened = Library::open(&file_path).unwrap();
+
+        assert_eq!(opened.created_at(), Some(DateTime::from(modified)));
+        assert_eq!(opened.updated_at(), None);
+    }
+
+    #[test]
+    fn open_assigns_id_to_book_without_one() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("spine.json");
+        std::fs::write(
+            &file_path,
+            r#"{"books": [{"title": "kim", "author": {"first_name": "rudyard", "surname": "kipling"}, "isbn": null, "status": "read"}]}"#,
+        )
+        .unwrap();
+
+        let opened = Library::open(&file_path).unwrap();
+
+        assert!(!opened.all().next().unwrap().id.is_nil());
+    }
+
+    #[test]
+    fn open_assigns_new_id_to_duplicate() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("spine.json");
+        let mut my_lib = library_with_two_books();
+        my_lib.books[1].id = BURMESE_DAYS.id;
+        my_lib.save(&file_path).unwrap();
+
+        let (opened, repaired) = Library::open_with_repairs(&file_path).unwrap();
+
+        let ids: Vec<_> = opened.all().map(|b| b.id).collect();
+        assert_eq!(repaired, 1);
+        assert_eq!(ids[0], BURMESE_DAYS.id);
+        assert_ne!(ids[1], BURMESE_DAYS.id);
+        assert!(!ids[1].is_nil());
+    }
+
+    #[test]
+    fn normalise_cleans_up_hand_edited_file() {
+        let mut opened = Library::open("tests/data/messy_book.json").unwrap();
+
+        let changed = opened.normalise();
+
+        assert_eq!(changed, 1);
+        let book = opened.all().next().unwrap();
+        assert_eq!(book.title, "hadji murat");
+        assert_eq!(book.author.to_string(), "leo tolstoy");
+        assert_eq!(book.isbn, Some(Isbn::from_str("9781847494818").unwrap()));
+        assert_eq!(
+            book.tags,
+            HashSet::from(["classic".into(), "russian".into()])
+        );
+        assert_eq!(book.notes.as_deref(), Some("lent to sam"));
+        assert_eq!(opened.find_by_isbn("9781847494818").count(), 1);
+    }
+
+    #[test]
+    fn open_optional_returns_none_for_absent_file() {
+        let tmp_dir = tempdir().unwrap();
+
+        let opened = Library::open_optional(tmp_dir.path().join("spine.json")).unwrap();
+
+        assert_eq!(opened, None);
+    }
+
+    #[test]
+    fn open_optional_with_repairs_returns_none_for_absent_file() {
+        let tmp_dir = tempdir().unwrap();
+
+        let opened =
+            Library::open_optional_with_repairs(tmp_dir.path().join("spine.json")).unwrap();
+
+        assert!(opened.is_none());
+    }
+
+    #[test]
+    fn open_optional_returns_some_for_valid_file() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("spine.json");
+        let mut my_lib = library_with_two_books();
+        my_lib.save(&file_path).unwrap();
+
+        let opened = Library::open_optional(&file_path).unwrap();
+
+        assert_eq!(opened, Some(my_lib));
+    }
+
+    #[test]
+    fn open_optional_throws_error_for_corrupt_file() {
+        let tmp_dir = tempdir().unwrap();
+        let file_path = tmp_dir.path().join("spine.json");
+        std::fs::write(&file_path, "{\"books\": [").unwrap();
+
+        let err = Library::open_optional(&file_path).unwrap_err();
+
+        assert!(matches!(err, SpineError::Serialization(_)));
+    }
 }
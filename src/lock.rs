@@ -0,0 +1,117 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions, TryLockError},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::SpineError;
+
+/// How often to retry while another process holds the lock.
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// An exclusive lock on a library file, released when dropped.
+///
+/// The lock is taken on a `<library>.lock` file beside the library, so it is held across the
+/// library being rewritten on save. The lock file is removed again on release.
+#[derive(Debug)]
+pub struct LibraryLock {
+    _file: File,
+    path: PathBuf,
+}
+
+impl LibraryLock {
+    /// Locks the library at `path`, waiting up to `timeout` for another process to release it.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`SpineError::Locked`] if the lock is still held after `timeout`, or if the lock
+    /// file can't be opened.
+    pub fn acquire(path: impl AsRef<Path>, timeout: Duration) -> Result<Self, SpineError> {
+        let path = lock_path(path.as_ref());
+        let deadline = Instant::now() + timeout;
+        loop {
+            let file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(false)
+                .open(&path)?;
+            match file.try_lock() {
+                Ok(()) if is_at_path(&file, &path) => return Ok(Self { _file: file, path }),
+                // The holder removed the file as it released it, so lock the new one instead.
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    thread::sleep(RETRY_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => return Err(SpineError::Locked),
+                Err(TryLockError::Error(e)) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for LibraryLock {
+    /// Removes the lock file while still holding the lock, before the file is closed.
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Returns whether `file` is still the file at `path`, rather than one since removed.
+#[cfg(unix)]
+fn is_at_path(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(opened), Ok(current)) => (opened.dev(), opened.ino()) == (current.dev(), current.ino()),
+        _ => false,
+    }
+}
+
+/// Returns whether `file` is still the file at `path`, rather than one since removed.
+///
+/// Unix lets a removed file still be opened and locked; elsewhere it's enough that it exists.
+#[cfg(not(unix))]
+fn is_at_path(_file: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+/// Returns the path of the lock file for the library at `path`.
+#[must_use]
+pub fn lock_path(path: &Path) -> PathBuf {
+    let mut lock = OsString::from(path.as_os_str());
+    lock.push(".lock");
+    PathBuf::from(lock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn acquire_fails_while_lock_is_held() {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("spine.json");
+        let held = LibraryLock::acquire(&path, Duration::ZERO).unwrap();
+
+        let err = LibraryLock::acquire(&path, Duration::from_millis(100)).unwrap_err();
+        drop(held);
+
+        assert!(matches!(err, SpineError::Locked));
+        assert!(LibraryLock::acquire(&path, Duration::ZERO).is_ok());
+    }
+
+    #[test]
+    fn release_removes_lock_file() {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("spine.json");
+
+        let held = LibraryLock::acquire(&path, Duration::ZERO).unwrap();
+        assert!(lock_path(&path).exists());
+        drop(held);
+
+        assert!(!lock_path(&path).exists());
+    }
+}
@@ -1,14 +1,44 @@
-fn main() -> anyhow::Result<()> {
-    if std::env::args().any(|arg| arg == "--cli") {
-        let cli_args = std::env::args().filter(|arg| arg != "--cli");
+use std::process::ExitCode;
+
+use clap::Parser;
+use spine::{cli::TuiArgs, tui::Tui};
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            ExitCode::from(spine::cli::exit_code(&e))
+        }
+    }
+}
+
+fn run() -> anyhow::Result<()> {
+    // Arguments may not be UTF-8, e.g. file names in another encoding, so are read as OS strings.
+    if std::env::args_os().any(|arg| arg == "--cli") {
+        let cli_args = std::env::args_os().filter(|arg| arg != "--cli");
         spine::cli::main(cli_args)
     } else {
+        let args = TuiArgs::parse();
+        let path = args.library_path()?;
         let mut terminal = ratatui::init();
+        let _restore = RestoreOnDrop;
         let term_size = terminal.get_frame().area();
-        let tui_result = spine::tui::Tui::new(term_size)
-            .expect("library should load")
-            .run(terminal);
+        let mut tui = Tui::new(&path, term_size)?;
+        if args.no_border {
+            tui = tui.borderless(term_size);
+        } else if args.border {
+            tui = tui.bordered(term_size);
+        }
+        Ok(tui.run(terminal)?)
+    }
+}
+
+/// Restores the terminal when dropped, so it isn't left in raw mode if the TUI exits early.
+struct RestoreOnDrop;
+
+impl Drop for RestoreOnDrop {
+    fn drop(&mut self) {
         ratatui::restore();
-        Ok(tui_result?)
     }
 }
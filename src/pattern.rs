@@ -0,0 +1,110 @@
+//! Searching titles and authors with regular expressions, for queries a substring can't express
+//! such as "starts with 'The' and ends in a year".
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{Book, Library, SpineError};
+
+/// Compiles `pattern` to match case-insensitively, unless it turns that off with `(?-i)`.
+///
+/// # Errors
+///
+/// Fails with [`SpineError::Validation`] if `pattern` isn't a valid regular expression.
+pub fn compile(pattern: &str) -> Result<Regex, SpineError> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| SpineError::Validation(format!("Invalid regex '{pattern}': {e}")))
+}
+
+impl Library {
+    /// Searches the library for books whose title, or author if `include_author` is set, matches
+    /// `regex`. Archived books are left out, as with [`Library::search`].
+    pub fn search_regex<'a>(
+        &'a self,
+        regex: &'a Regex,
+        include_author: bool,
+    ) -> impl Iterator<Item = &'a Book> {
+        self.all().filter(move |b| {
+            !b.archived
+                && (regex.is_match(&b.title)
+                    || include_author && regex.is_match(&b.author.to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Author;
+    use std::str::FromStr;
+
+    fn library() -> Library {
+        let mut library = Library::new();
+        for (title, author) in [
+            ("The Remains of the Day 1956", "kazuo ishiguro"),
+            ("the road to wigan pier 1937", "george orwell"),
+            ("The Road", "cormac mccarthy"),
+            ("Nineteen Eighty-Four", "george orwell"),
+        ] {
+            library.add(Book {
+                title: title.to_owned(),
+                author: Author::from_str(author).unwrap(),
+                ..Default::default()
+            });
+        }
+        library
+    }
+
+    fn titles<'a>(books: impl Iterator<Item = &'a Book>) -> Vec<&'a str> {
+        books.map(|b| b.title.as_str()).collect()
+    }
+
+    #[test]
+    fn search_regex_matches_titles_ignoring_case() {
+        let library = library();
+        let regex = compile(r"^the .*\d{4}$").unwrap();
+
+        let hits = titles(library.search_regex(&regex, false));
+
+        assert_eq!(
+            hits,
+            ["The Remains of the Day 1956", "the road to wigan pier 1937"]
+        );
+    }
+
+    #[test]
+    fn search_regex_matches_author_only_when_asked() {
+        let library = library();
+        let regex = compile("^george").unwrap();
+
+        assert!(library.search_regex(&regex, false).next().is_none());
+        assert_eq!(library.search_regex(&regex, true).count(), 2);
+    }
+
+    #[test]
+    fn search_regex_can_turn_off_ignoring_case() {
+        let library = library();
+        let regex = compile("(?-i)^The").unwrap();
+
+        assert_eq!(library.search_regex(&regex, false).count(), 2);
+    }
+
+    #[test]
+    fn search_regex_leaves_out_archived_books() {
+        let mut library = library();
+        let id = library.all().next().unwrap().id;
+        library.set_archived(id, true).unwrap();
+        let regex = compile("day").unwrap();
+
+        assert!(library.search_regex(&regex, false).next().is_none());
+    }
+
+    #[test]
+    fn compile_rejects_invalid_pattern() {
+        let err = compile("the (road").unwrap_err();
+
+        assert!(matches!(err, SpineError::Validation(_)));
+        assert!(err.to_string().contains("Invalid regex 'the (road'"));
+    }
+}
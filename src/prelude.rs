@@ -0,0 +1,34 @@
+//! The types most code using spine needs, to import in one line with `use spine::prelude::*`.
+//!
+//! These names stay here even if the modules defining them move.
+
+pub use crate::{Author, Book, BookBuilder, Isbn, Library, LibrarySearch, SpineError, Status};
+
+#[cfg(test)]
+mod tests {
+    // Imports only what `use spine::prelude::*` would.
+    use super::*;
+
+    #[test]
+    fn prelude_exposes_common_types() {
+        let book: Book = BookBuilder::default()
+            .title("kim")
+            .author("rudyard kipling")
+            .isbn("9780199536467")
+            .status(Status::Read)
+            .build()
+            .unwrap();
+        let mut library = Library::new();
+        library.add(book);
+        let search = LibrarySearch {
+            title: Some("kim".into()),
+            ..Default::default()
+        };
+
+        let hit = library.search(&search).next().unwrap();
+
+        assert_eq!(hit.author, "rudyard kipling".parse::<Author>().unwrap());
+        assert_eq!(hit.isbn, "9780199536467".parse::<Isbn>().ok());
+        assert_eq!(SpineError::NotFound.exit_code(), 1);
+    }
+}
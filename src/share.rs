@@ -0,0 +1,122 @@
+//! Compact, copy-paste-friendly snippets of a single book, for sending to someone who can add it
+//! to their own library with `spine add --from`.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+
+use crate::{Book, SpineError, Status};
+
+/// Start of every snippet, so it's recognisable as a spine book in a message.
+pub const SNIPPET_PREFIX: &str = "spine://book/";
+
+/// The details of a book that are shared in a snippet.
+///
+/// Displays as the snippet: [`SNIPPET_PREFIX`] followed by the details as URL-safe base64 JSON,
+/// which has no spaces or punctuation a chat app might mangle.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SharedBook {
+    pub title: String,
+    pub author: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isbn: Option<String>,
+    #[serde(default)]
+    pub status: Status,
+}
+
+impl From<&Book> for SharedBook {
+    fn from(book: &Book) -> Self {
+        Self {
+            title: book.title.clone(),
+            author: book.author.to_string(),
+            isbn: book.isbn.as_ref().map(|isbn| isbn.as_str().to_owned()),
+            status: book.status,
+        }
+    }
+}
+
+impl Display for SharedBook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let json = serde_json::to_vec(self).map_err(|_| fmt::Error)?;
+        write!(f, "{SNIPPET_PREFIX}{}", URL_SAFE_NO_PAD.encode(json))
+    }
+}
+
+impl FromStr for SharedBook {
+    type Err = SpineError;
+
+    /// Parses a snippet, with or without [`SNIPPET_PREFIX`] and any whitespace around it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SpineError::Validation("Invalid snippet: not a shared spine book.".into());
+        let encoded = s.trim();
+        let encoded = encoded.strip_prefix(SNIPPET_PREFIX).unwrap_or(encoded);
+        let json = URL_SAFE_NO_PAD.decode(encoded).map_err(|_| invalid())?;
+        serde_json::from_slice(&json).map_err(|_| invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Author, Isbn};
+
+    fn book() -> Book {
+        Book {
+            title: "ノルウェイの森 & other \"stories\"".to_owned(),
+            author: Author::from_str("haruki murakami").unwrap(),
+            isbn: Some(Isbn::from_str("9780099448822").unwrap()),
+            status: Status::Reading,
+            notes: Some("left at a friend's".to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn snippet_round_trips_title_author_isbn_and_status() {
+        let snippet = SharedBook::from(&book()).to_string();
+
+        let shared = SharedBook::from_str(&snippet).unwrap();
+
+        assert_eq!(shared, SharedBook::from(&book()));
+        assert_eq!(shared.status, Status::Reading);
+        assert_eq!(shared.isbn.as_deref(), Some("9780099448822"));
+    }
+
+    #[test]
+    fn snippet_is_one_word_starting_with_prefix() {
+        let snippet = SharedBook::from(&book()).to_string();
+
+        assert!(snippet.starts_with(SNIPPET_PREFIX));
+        let encoded = &snippet[SNIPPET_PREFIX.len()..];
+        assert!(
+            encoded
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+            "{snippet}"
+        );
+    }
+
+    #[test]
+    fn snippet_parses_without_prefix_and_with_whitespace() {
+        let snippet = SharedBook::from(&book()).to_string();
+        let encoded = snippet.trim_start_matches(SNIPPET_PREFIX);
+
+        let shared = SharedBook::from_str(&format!("  {encoded}\n")).unwrap();
+
+        assert_eq!(shared.title, book().title);
+    }
+
+    #[test]
+    fn snippet_rejects_text_that_is_not_a_shared_book() {
+        let not_json = format!("{SNIPPET_PREFIX}{}", URL_SAFE_NO_PAD.encode("kim"));
+        for snippet in ["spine://book/!!!", not_json.as_str(), ""] {
+            let err = SharedBook::from_str(snippet).unwrap_err();
+
+            assert!(matches!(err, SpineError::Validation(_)), "{snippet}");
+        }
+    }
+}
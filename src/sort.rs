@@ -0,0 +1,313 @@
+//! Orders for listing books, such as within each status when books are grouped by status.
+
+use std::{
+    cmp::{Ordering, Reverse},
+    str::FromStr,
+};
+
+use crate::{Book, Library, SpineError, Status};
+
+/// Field of a book to sort by.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SortKey {
+    Added,
+    Started,
+    Finished,
+    /// [`Book::priority`], 1 first.
+    Priority,
+    /// Title, ignoring leading articles as in [`Book::title_sort_key`].
+    Title,
+    Author,
+}
+
+/// A field to sort by and which way. Books without the field come last either way, and books
+/// that tie keep the order they were added in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SortOrder {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl SortOrder {
+    #[must_use]
+    pub const fn ascending(key: SortKey) -> Self {
+        Self {
+            key,
+            descending: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn descending(key: SortKey) -> Self {
+        Self {
+            key,
+            descending: true,
+        }
+    }
+
+    /// Sorts `books` in this order.
+    pub fn sort(self, books: &mut [&Book]) {
+        match self.key {
+            SortKey::Added => books.sort_by(|a, b| self.compare(a.date_added, b.date_added)),
+            SortKey::Started => books.sort_by(|a, b| self.compare(a.date_started, b.date_started)),
+            SortKey::Finished => {
+                books.sort_by(|a, b| self.compare(a.date_finished, b.date_finished));
+            }
+            SortKey::Priority => books.sort_by(|a, b| self.compare(a.priority, b.priority)),
+            SortKey::Title if self.descending => {
+                books.sort_by_cached_key(|b| Reverse(b.title_sort_key()));
+            }
+            SortKey::Title => books.sort_by_cached_key(|b| b.title_sort_key()),
+            SortKey::Author if self.descending => {
+                books.sort_by_cached_key(|b| Reverse(b.author.to_string().to_lowercase()));
+            }
+            SortKey::Author => books.sort_by_cached_key(|b| b.author.to_string().to_lowercase()),
+        }
+    }
+
+    /// Compares two values of the field, putting missing values last.
+    fn compare<T: Ord>(self, a: Option<T>, b: Option<T>) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) if self.descending => b.cmp(&a),
+            (Some(a), Some(b)) => a.cmp(&b),
+            (a, b) => a.is_none().cmp(&b.is_none()),
+        }
+    }
+}
+
+impl FromStr for SortOrder {
+    type Err = SpineError;
+
+    /// Parses a key such as "finished", with "-desc" on the end to sort descending.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let (key, descending) = s
+            .strip_suffix("-desc")
+            .map_or((s.as_str(), false), |key| (key, true));
+        let key = match key {
+            "added" => SortKey::Added,
+            "started" => SortKey::Started,
+            "finished" => SortKey::Finished,
+            "priority" => SortKey::Priority,
+            "title" => SortKey::Title,
+            "author" => SortKey::Author,
+            _ => {
+                return Err(SpineError::Validation(format!(
+                    "Invalid sort '{s}': expected 'added', 'started', 'finished', 'priority', \
+                     'title' or 'author', optionally ending in '-desc'."
+                )));
+            }
+        };
+        Ok(Self { key, descending })
+    }
+}
+
+/// The order to list books in within each status, parsed from e.g.
+/// `read=finished-desc,want=priority`. Statuses left out keep their default order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GroupSort {
+    pub want: SortOrder,
+    pub reading: SortOrder,
+    pub read: SortOrder,
+}
+
+impl Default for GroupSort {
+    /// Want to read by priority, reading by when they were started, and read newest-finished
+    /// first.
+    fn default() -> Self {
+        Self {
+            want: SortOrder::ascending(SortKey::Priority),
+            reading: SortOrder::ascending(SortKey::Started),
+            read: SortOrder::descending(SortKey::Finished),
+        }
+    }
+}
+
+impl GroupSort {
+    /// Returns the order for books with `status`.
+    #[must_use]
+    pub const fn for_status(&self, status: Status) -> SortOrder {
+        match status {
+            Status::Want => self.want,
+            Status::Reading => self.reading,
+            Status::Read => self.read,
+        }
+    }
+}
+
+impl FromStr for GroupSort {
+    type Err = SpineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut group_sort = Self::default();
+        for pair in s.split(',') {
+            let (status, order) = pair.split_once('=').ok_or_else(|| {
+                SpineError::Validation(format!(
+                    "Invalid group sort '{}': expected status=sort.",
+                    pair.trim()
+                ))
+            })?;
+            let order = order.parse()?;
+            match status.parse()? {
+                Status::Want => group_sort.want = order,
+                Status::Reading => group_sort.reading = order,
+                Status::Read => group_sort.read = order,
+            }
+        }
+        Ok(group_sort)
+    }
+}
+
+impl Library {
+    /// Returns the books to read in the order to read them: by priority, then oldest added first.
+    /// Archived books are left out.
+    #[must_use]
+    pub fn want_queue(&self) -> Vec<&Book> {
+        let mut queue: Vec<&Book> = self
+            .by_status(Status::Want)
+            .filter(|b| !b.archived)
+            .collect();
+        SortOrder::ascending(SortKey::Added).sort(&mut queue);
+        SortOrder::ascending(SortKey::Priority).sort(&mut queue);
+        queue
+    }
+
+    /// Returns the book at the front of [`Library::want_queue`], if there is one.
+    #[must_use]
+    pub fn next_to_read(&self) -> Option<&Book> {
+        self.want_queue().first().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn day(d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(2024, 3, d)
+    }
+
+    fn titles<'a>(books: &[&'a Book]) -> Vec<&'a str> {
+        books.iter().map(|b| b.title.as_str()).collect()
+    }
+
+    fn sorted(books: &[Book], status: Status, group_sort: GroupSort) -> Vec<&str> {
+        let mut refs: Vec<&Book> = books.iter().collect();
+        group_sort.for_status(status).sort(&mut refs);
+        titles(&refs)
+    }
+
+    #[test]
+    fn default_sorts_read_books_newest_finished_first() {
+        let books = [
+            ("kim", day(1)),
+            ("emma", None),
+            ("dune", day(20)),
+            ("ulysses", day(9)),
+        ]
+        .map(|(title, finished)| Book {
+            title: title.to_owned(),
+            date_finished: finished,
+            ..Default::default()
+        });
+
+        let order = sorted(&books, Status::Read, GroupSort::default());
+
+        assert_eq!(order, ["dune", "ulysses", "kim", "emma"]);
+    }
+
+    #[test]
+    fn default_sorts_reading_books_earliest_started_first() {
+        let books =
+            [("kim", day(9)), ("emma", None), ("dune", day(1))].map(|(title, started)| Book {
+                title: title.to_owned(),
+                date_started: started,
+                ..Default::default()
+            });
+
+        let order = sorted(&books, Status::Reading, GroupSort::default());
+
+        assert_eq!(order, ["dune", "kim", "emma"]);
+    }
+
+    #[test]
+    fn default_sorts_want_books_by_priority_keeping_added_order_for_ties() {
+        let books = [
+            ("kim", None),
+            ("emma", Some(2)),
+            ("dune", Some(1)),
+            ("ulysses", Some(2)),
+            ("walden", None),
+        ]
+        .map(|(title, priority)| Book {
+            title: title.to_owned(),
+            priority,
+            ..Default::default()
+        });
+
+        let order = sorted(&books, Status::Want, GroupSort::default());
+
+        assert_eq!(order, ["dune", "emma", "ulysses", "kim", "walden"]);
+    }
+
+    #[test]
+    fn group_sort_parses_overrides_and_keeps_other_defaults() {
+        let group_sort: GroupSort = "want=title, Read=author-desc".parse().unwrap();
+
+        assert_eq!(group_sort.want, SortOrder::ascending(SortKey::Title));
+        assert_eq!(group_sort.read, SortOrder::descending(SortKey::Author));
+        assert_eq!(group_sort.reading, GroupSort::default().reading);
+    }
+
+    #[test]
+    fn group_sort_rejects_unknown_status_or_key() {
+        for text in ["read", "abandoned=title", "read=rating"] {
+            assert!(
+                matches!(GroupSort::from_str(text), Err(SpineError::Validation(_))),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn next_to_read_picks_highest_priority_then_oldest_want_book() {
+        let mut library = Library::new();
+        for (title, status, priority, added, archived) in [
+            ("kim", Status::Want, None, day(1), false),
+            ("emma", Status::Want, Some(2), day(3), false),
+            ("dune", Status::Reading, Some(1), day(1), false),
+            ("walden", Status::Want, Some(1), day(1), true),
+            ("ulysses", Status::Want, Some(2), day(2), false),
+        ] {
+            library.add(Book {
+                title: title.to_owned(),
+                status,
+                priority,
+                date_added: added,
+                archived,
+                ..Default::default()
+            });
+        }
+
+        assert_eq!(titles(&library.want_queue()), ["ulysses", "emma", "kim"]);
+        assert_eq!(
+            library.next_to_read().map(|b| b.title.as_str()),
+            Some("ulysses")
+        );
+        assert_eq!(Library::new().next_to_read(), None);
+    }
+
+    #[test]
+    fn title_sort_ignores_articles_and_can_descend() {
+        let books = ["The Zoo", "a bear", "Moby Dick"].map(|title| Book {
+            title: title.to_owned(),
+            ..Default::default()
+        });
+        let mut refs: Vec<&Book> = books.iter().collect();
+
+        SortOrder::descending(SortKey::Title).sort(&mut refs);
+
+        assert_eq!(titles(&refs), ["The Zoo", "Moby Dick", "a bear"]);
+    }
+}
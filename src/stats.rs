@@ -0,0 +1,474 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    io::Write,
+    str::FromStr,
+};
+
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::{Book, Library, Money, SpineError, Status};
+
+/// Counts of the books in a library by status, and of the books finished each year, with the
+/// current weekly reading streak.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LibraryStats {
+    pub total: usize,
+    pub want: usize,
+    pub reading: usize,
+    pub read: usize,
+    /// Books finished in each year, counting only books with a finish date.
+    pub finished_by_year: BTreeMap<i32, usize>,
+    /// Books in each language, counting only books with a language.
+    #[serde(default)]
+    pub by_language: BTreeMap<String, usize>,
+    /// Weeks in a row in which a book was finished, from [`Library::reading_streak`].
+    #[serde(default)]
+    pub streak_weeks: u32,
+}
+
+impl LibraryStats {
+    /// Collects the stats of a library, working out the reading streak as of `today`.
+    #[must_use]
+    pub fn new(library: &Library, today: NaiveDate) -> Self {
+        let mut stats = Self {
+            total: library.all().len(),
+            want: library.by_status(Status::Want).count(),
+            reading: library.by_status(Status::Reading).count(),
+            read: library.by_status(Status::Read).count(),
+            streak_weeks: library.reading_streak(today),
+            ..Self::default()
+        };
+        for book in library.all() {
+            if let Some(finished) = book.date_finished {
+                *stats.finished_by_year.entry(finished.year()).or_default() += 1;
+            }
+            if let Some(language) = &book.language {
+                *stats.by_language.entry(language.clone()).or_default() += 1;
+            }
+        }
+        stats
+    }
+
+    /// Writes the stats as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `writer` can't be written to.
+    pub fn write_json(&self, writer: &mut impl Write) -> Result<(), SpineError> {
+        serde_json::to_writer_pretty(&mut *writer, self)?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+
+    /// Writes the books finished each year as CSV, one row per year.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `writer` can't be written to.
+    pub fn write_csv(&self, writer: &mut impl Write) -> Result<(), SpineError> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer
+            .write_record(["year", "finished"])
+            .map_err(io_error)?;
+        for (year, finished) in &self.finished_by_year {
+            csv_writer
+                .write_record([year.to_string(), finished.to_string()])
+                .map_err(io_error)?;
+        }
+        csv_writer.flush()?;
+
+        Ok(())
+    }
+}
+
+impl Display for LibraryStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<10}{}", "Total", self.total)?;
+        writeln!(f, "{:<10}{}", "Want", self.want)?;
+        writeln!(f, "{:<10}{}", "Reading", self.reading)?;
+        writeln!(f, "{:<10}{}", "Read", self.read)?;
+        writeln!(f, "{:<10}{} week(s)", "Streak", self.streak_weeks)?;
+        if !self.finished_by_year.is_empty() {
+            writeln!(f, "\nFinished by year")?;
+            for (year, finished) in &self.finished_by_year {
+                writeln!(f, "{year:<10}{finished}")?;
+            }
+        }
+        if !self.by_language.is_empty() {
+            writeln!(f, "\nBy language")?;
+            for (language, count) in &self.by_language {
+                writeln!(f, "{language:<10}{count}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Span of time a [`Report`] covers.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Period {
+    /// Monday to Sunday.
+    Week,
+    /// The calendar month.
+    #[default]
+    Month,
+}
+
+impl Period {
+    /// Returns the first and last day of the period `today` falls in.
+    #[must_use]
+    pub fn bounds(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            Self::Week => {
+                let week = today.week(Weekday::Mon);
+                (week.first_day(), week.last_day())
+            }
+            Self::Month => {
+                let first = today - Days::new(u64::from(today.day0()));
+                (first, first + Months::new(1) - Days::new(1))
+            }
+        }
+    }
+}
+
+impl FromStr for Period {
+    type Err = SpineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "week" => Ok(Self::Week),
+            "month" => Ok(Self::Month),
+            _ => Err(SpineError::Validation(
+                "Invalid period: expected 'week' or 'month'".into(),
+            )),
+        }
+    }
+}
+
+/// The books finished, started and added in the week or month so far.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Report<'a> {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Books finished in the period, by finish date.
+    pub finished: Vec<&'a Book>,
+    /// Books last moved to reading in the period, by start date.
+    pub started: Vec<&'a Book>,
+    /// Books added in the period that are still in the library, by date added.
+    pub added: Vec<&'a Book>,
+}
+
+impl<'a> Report<'a> {
+    /// Collects the books of the `period` that `today` falls in.
+    #[must_use]
+    pub fn new(library: &'a Library, period: Period, today: NaiveDate) -> Self {
+        let (start, end) = period.bounds(today);
+        let in_period = |date: fn(&Book) -> Option<NaiveDate>| {
+            let mut books: Vec<&Book> = library
+                .all()
+                .filter(|b| date(b).is_some_and(|d| start <= d && d <= end))
+                .collect();
+            books.sort_by_key(|b| date(b));
+            books
+        };
+        Self {
+            start,
+            end,
+            finished: in_period(|b| b.date_finished),
+            started: in_period(|b| b.date_started),
+            added: in_period(|b| b.date_added),
+        }
+    }
+}
+
+impl Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Reading report for {} to {}", self.start, self.end)?;
+        for (heading, books) in [
+            ("Finished", &self.finished),
+            ("Started", &self.started),
+            ("Added", &self.added),
+        ] {
+            writeln!(f, "\n{heading} ({})", books.len())?;
+            for book in books {
+                writeln!(f, "  {book}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Totals the prices of the books in a library, one total per currency, ordered by currency.
+#[must_use]
+pub fn spent_by_currency(library: &Library) -> Vec<Money> {
+    let mut totals: BTreeMap<&str, u64> = BTreeMap::new();
+    for price in library.all().filter_map(|b| b.price.as_ref()) {
+        *totals.entry(price.currency()).or_default() += price.amount();
+    }
+    totals
+        .into_iter()
+        .filter_map(|(currency, amount)| Money::new(amount, currency).ok())
+        .collect()
+}
+
+/// Groups the books in a library by where they're kept, ordered by location. Books without a
+/// location are left out.
+#[must_use]
+pub fn books_by_location(library: &Library) -> BTreeMap<&str, Vec<&Book>> {
+    let mut by_location: BTreeMap<&str, Vec<&Book>> = BTreeMap::new();
+    for book in library.all() {
+        if let Some(location) = &book.location {
+            by_location.entry(location).or_default().push(book);
+        }
+    }
+    by_location
+}
+
+/// Describes how much disk space a library of `count` books takes up, given the size of its file
+/// in bytes, or `None` if it hasn't been saved yet.
+#[must_use]
+pub fn format_file_size(size: Option<u64>, count: usize) -> String {
+    let Some(size) = size else {
+        return format!("{:<10}not yet saved", "File size");
+    };
+    let total = format!("{:<10}{size} bytes", "File size");
+    match u64::try_from(count) {
+        Ok(count) if count > 0 => format!("{total}\n{:<10}{} bytes", "Per book", size / count),
+        _ => total,
+    }
+}
+
+fn io_error(err: csv::Error) -> SpineError {
+    SpineError::Io(err.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Author;
+    use std::str::FromStr;
+
+    const TODAY: NaiveDate = NaiveDate::from_ymd_opt(2024, 10, 1).unwrap();
+
+    fn library() -> Library {
+        let mut library = Library::new();
+        for (title, status, finished) in [
+            ("burmese days", Status::Want, None),
+            ("animal farm", Status::Reading, None),
+            ("coming up for air", Status::Read, Some((2023, 5, 1))),
+            ("homage to catalonia", Status::Read, Some((2024, 2, 14))),
+            (
+                "down and out in paris and london",
+                Status::Read,
+                Some((2024, 9, 30)),
+            ),
+        ] {
+            library.add(Book {
+                title: title.to_owned(),
+                author: Author::from_str("george orwell").unwrap(),
+                status,
+                date_finished: finished.map(|(y, m, d)| NaiveDate::from_ymd_opt(y, m, d).unwrap()),
+                ..Default::default()
+            });
+        }
+        library
+    }
+
+    /// A library whose books were added, started and finished on the given days of 2024, with
+    /// TODAY falling on Tuesday 1 October.
+    fn library_with_transitions() -> Library {
+        let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let mut library = Library::new();
+        for (title, added, started, finished) in [
+            (
+                "burmese days",
+                day(9, 2),
+                Some(day(9, 30)),
+                Some(day(10, 1)),
+            ),
+            ("animal farm", day(9, 30), Some(day(10, 1)), None),
+            ("coming up for air", day(10, 1), None, None),
+            (
+                "homage to catalonia",
+                day(8, 1),
+                Some(day(8, 2)),
+                Some(day(9, 30)),
+            ),
+        ] {
+            let mut book = Book {
+                title: title.to_owned(),
+                author: Author::from_str("george orwell").unwrap(),
+                date_added: Some(added),
+                ..Default::default()
+            };
+            if let Some(started) = started {
+                book.set_status(Status::Reading, started);
+            }
+            if let Some(finished) = finished {
+                book.set_status(Status::Read, finished);
+            }
+            library.add(book);
+        }
+        library
+    }
+
+    fn titles<'a>(books: &[&'a Book]) -> Vec<&'a str> {
+        books.iter().map(|b| b.title.as_str()).collect()
+    }
+
+    #[test]
+    fn period_bounds_cover_week_from_monday_and_whole_month() {
+        let leap_day = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+
+        let week = Period::Week.bounds(leap_day);
+        let month = Period::Month.bounds(leap_day);
+
+        let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        assert_eq!(week, (day(2, 26), day(3, 3)));
+        assert_eq!(month, (day(2, 1), day(2, 29)));
+    }
+
+    #[test]
+    fn period_parses_week_and_month_and_rejects_others() {
+        assert_eq!(Period::from_str(" Week ").unwrap(), Period::Week);
+        assert_eq!(Period::from_str("month").unwrap(), Period::Month);
+        assert!(matches!(
+            Period::from_str("year"),
+            Err(SpineError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn report_for_week_lists_books_finished_started_and_added() {
+        let library = library_with_transitions();
+
+        let report = Report::new(&library, Period::Week, TODAY);
+
+        assert_eq!(
+            titles(&report.finished),
+            ["homage to catalonia", "burmese days"]
+        );
+        assert_eq!(titles(&report.started), ["burmese days", "animal farm"]);
+        assert_eq!(titles(&report.added), ["animal farm", "coming up for air"]);
+    }
+
+    #[test]
+    fn report_for_month_leaves_out_earlier_months() {
+        let library = library_with_transitions();
+
+        let report = Report::new(&library, Period::Month, TODAY);
+
+        assert_eq!(titles(&report.finished), ["burmese days"]);
+        assert_eq!(titles(&report.started), ["animal farm"]);
+        assert_eq!(titles(&report.added), ["coming up for air"]);
+        assert_eq!(
+            report.to_string(),
+            concat!(
+                "Reading report for 2024-10-01 to 2024-10-31\n",
+                "\nFinished (1)\n  burmese days, george orwell\n",
+                "\nStarted (1)\n  animal farm, george orwell\n",
+                "\nAdded (1)\n  coming up for air, george orwell\n",
+            )
+        );
+    }
+
+    #[test]
+    fn write_json_round_trips_per_year_counts() {
+        let stats = LibraryStats::new(&library(), TODAY);
+        let mut out = Vec::new();
+
+        stats.write_json(&mut out).unwrap();
+        let parsed: LibraryStats = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(parsed, stats);
+        assert_eq!(
+            parsed.finished_by_year,
+            BTreeMap::from([(2023, 1), (2024, 2)])
+        );
+        assert_eq!((parsed.want, parsed.reading, parsed.read), (1, 1, 3));
+        assert_eq!(parsed.streak_weeks, 1);
+    }
+
+    #[test]
+    fn new_counts_books_by_language() {
+        let mut library = library();
+        let ids: Vec<_> = library.all().map(|b| b.id).collect();
+        for (&id, language) in ids.iter().zip(["en", "fr", "en"]) {
+            library.get_mut(id).unwrap().language = Some(language.to_owned());
+        }
+
+        let stats = LibraryStats::new(&library, TODAY);
+
+        assert_eq!(
+            stats.by_language,
+            BTreeMap::from([("en".to_owned(), 2), ("fr".to_owned(), 1)])
+        );
+    }
+
+    #[test]
+    fn spent_by_currency_sums_each_currency() {
+        let mut library = library();
+        for (book, price) in library
+            .all()
+            .map(|b| b.id)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .zip(["12.99 GBP", "20 USD", "7.01 GBP"])
+        {
+            library.get_mut(book).unwrap().price = Some(price.parse().unwrap());
+        }
+
+        let spent: Vec<_> = spent_by_currency(&library)
+            .iter()
+            .map(Money::to_string)
+            .collect();
+
+        assert_eq!(spent, ["20.00 GBP", "20.00 USD"]);
+    }
+
+    #[test]
+    fn books_by_location_groups_books_with_a_location() {
+        let mut library = library();
+        let ids: Vec<_> = library.all().map(|b| b.id).collect();
+        for (&id, location) in ids.iter().zip(["Study", "Hall"]) {
+            library.get_mut(id).unwrap().location = Some(location.to_owned());
+        }
+
+        let grouped: Vec<_> = books_by_location(&library)
+            .into_iter()
+            .map(|(location, books)| (location, books.iter().map(|b| b.id).collect::<Vec<_>>()))
+            .collect();
+
+        assert_eq!(grouped, [("Hall", vec![ids[1]]), ("Study", vec![ids[0]])]);
+    }
+
+    #[test]
+    fn format_file_size_shows_average_bytes_per_book() {
+        let formatted = format_file_size(Some(1000), 4);
+
+        assert_eq!(formatted, "File size 1000 bytes\nPer book  250 bytes");
+    }
+
+    #[test]
+    fn format_file_size_shows_not_yet_saved_without_file() {
+        let formatted = format_file_size(None, 0);
+
+        assert_eq!(formatted, "File size not yet saved");
+    }
+
+    #[test]
+    fn write_csv_writes_one_row_per_year() {
+        let stats = LibraryStats::new(&library(), TODAY);
+        let mut out = Vec::new();
+
+        stats.write_csv(&mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "year,finished\n2023,1\n2024,2\n"
+        );
+    }
+}
@@ -1,18 +1,60 @@
-use std::{collections::HashSet, io, path::Path};
+use std::{
+    collections::HashSet,
+    fs, io, mem,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
 
 use ratatui::{
     DefaultTerminal, Frame,
     buffer::Buffer,
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-    layout::Rect,
-    style::Stylize,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
     symbols::border,
     text::Line,
-    widgets::{Block, List, ListItem, Widget},
+    widgets::{Block, Clear, List, ListItem, Widget},
 };
+use serde::{Deserialize, Serialize};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 use uuid::Uuid;
 
-use crate::{Library, LibrarySearch, Status};
+use crate::{
+    Book, Isbn, Library, LibrarySearch, SpineError, Status, book::MAX_RATING, clock::SystemClock,
+    config::Config, lock::LibraryLock,
+};
+
+/// Rows taken up by the borders and the status message line.
+const CHROME_ROWS: u16 = 3;
+
+/// Rows taken up by the status message and instructions lines when there is no border.
+const BORDERLESS_CHROME_ROWS: u16 = 2;
+
+/// Narrowest a column of books can be before the list falls back to fewer columns.
+const MIN_COLUMN_WIDTH: u16 = 50;
+
+/// Number of redraws a status message stays on screen for.
+const STATUS_MESSAGE_REDRAWS: u8 = 3;
+
+/// How long to wait for input before checking whether the library file has changed.
+const FILE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long the library file must stay unchanged before it is reloaded, so a burst of writes
+/// only reloads it once.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// How long the TUI waits after the last change before saving the library, so a burst of
+/// changes is written once.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// How long to wait for a spine command to finish with the library before saving.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Cells in the progress bar shown for the book under the cursor.
+const PROGRESS_BAR_CELLS: u8 = 5;
+
+/// File the TUI's preferences are kept in between runs, beside the library file.
+const PREFERENCES_FILE: &str = "spine_tui.toml";
 
 #[derive(Debug, Default)]
 pub struct Tui {
@@ -20,50 +62,481 @@ pub struct Tui {
     library: Library,
     cursor: usize,
     scroll_offset: usize,
+    /// Rows of books visible in each column.
     num_visible: usize,
+    /// Columns the books are flowed down, filling each column before the next.
+    columns: usize,
     filtered: Vec<Uuid>,
+    /// Books ticked for a batch action.
+    selected: HashSet<Uuid>,
+    /// Filter applied to the listed books, if any.
+    filter: Option<Filter>,
+    /// Books page up and page down move by, if changed from a whole page.
+    page_jump: Option<usize>,
+    /// Key pressed once of a binding that needs it twice, e.g. the first `g` of `gg`.
+    pending_key: Option<char>,
+    /// Whether the list of every key binding is shown over the books.
+    show_help: bool,
+    /// Outcome of the last action, shown above the instructions.
+    status_message: Option<String>,
+    /// Redraws left before the status message is cleared.
+    status_ttl: u8,
+    /// Set when the state has changed since the last redraw.
+    dirty: bool,
+    /// File the library was loaded from, reloaded when it changes on disk.
+    path: PathBuf,
+    /// Modification time of the library file when it was last loaded.
+    loaded_modified: Option<SystemTime>,
+    /// Latest modification time seen on the library file, and when it was first seen.
+    seen_modified: Option<(SystemTime, Instant)>,
+    /// When the library was last changed, if it has changes that aren't saved yet.
+    last_unsaved_change: Option<Instant>,
+    /// Whether the list is drawn inside a border.
+    border: Border,
+    /// File the preferences are saved to on quit, if any.
+    preferences_path: Option<PathBuf>,
 }
 
+/// Filters that can be applied to the listed books.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Filter {
+    /// Books the user wants to read.
+    Want,
+}
+
+impl Filter {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Want => "want",
+        }
+    }
+}
+
+/// Settings the TUI remembers between runs, saved as TOML.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+struct Preferences {
+    filter: Option<Filter>,
+    borderless: bool,
+    /// Books page up and page down move by, if changed from a whole page.
+    page_jump: Option<usize>,
+}
+
+impl Preferences {
+    /// Reads the preferences saved at `path`, or the defaults if there are none.
+    fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                toml::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Saves the preferences to `path`, creating its directory if need be.
+    fn save(&self, path: &Path) -> io::Result<()> {
+        let text = toml::to_string(self).map_err(io::Error::other)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, text)
+    }
+
+    /// Returns where the preferences are kept: beside the config file at `config_path`, or
+    /// beside the library at `library_path` if there is no config directory.
+    fn path(config_path: Option<&Path>, library_path: &Path) -> PathBuf {
+        config_path
+            .unwrap_or(library_path)
+            .with_file_name(PREFERENCES_FILE)
+    }
+}
+
+/// Whether the list is drawn inside a border, with the title and instructions on it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum Border {
+    #[default]
+    Shown,
+    /// No border, with the instructions on a line of their own below the list.
+    Hidden,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum Message {
     Quit,
-    Resize(usize),
+    Resize { rows: usize, columns: usize },
     CursorUp,
     CursorDown,
+    ColumnLeft,
+    ColumnRight,
     PageUp,
     PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    Top,
+    Bottom,
     ApplyFilter,
+    ClearFilter,
+    OpenBookPage,
+    GrowPageJump,
+    ShrinkPageJump,
+    ToggleSelected,
+    MarkSelectedRead,
+    ToggleHelp,
+}
+
+/// A key press that triggers a [`KeyBinding`].
+#[derive(Clone, Copy, Debug)]
+enum Trigger {
+    /// The key, with or without modifiers.
+    Key(KeyCode),
+    /// The key with Ctrl held.
+    Ctrl(char),
+    /// The key pressed twice in a row.
+    Twice(char),
+}
+
+impl Trigger {
+    /// Returns whether `key` completes the trigger, given the key pressed once before it if that
+    /// was the first half of a [`Trigger::Twice`].
+    fn matches(self, key: KeyEvent, pending: Option<char>) -> bool {
+        match self {
+            Self::Key(code) => key.code == code,
+            Self::Ctrl(c) => {
+                key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char(c)
+            }
+            Self::Twice(c) => pending == Some(c) && key.code == KeyCode::Char(c),
+        }
+    }
 }
 
+/// An action and the keys that trigger it.
+struct KeyBinding {
+    /// The keys as shown in the instructions and help, e.g. "Up/k".
+    keys: &'static str,
+    action: &'static str,
+    triggers: &'static [Trigger],
+    message: Message,
+    /// Whether the binding is listed in the instructions line, rather than only in the help.
+    in_instructions: bool,
+}
+
+/// Every key binding, which [`Tui::handle_key_event`] looks keys up in and the instructions and
+/// help are drawn from. The first binding a key triggers wins.
+const KEY_BINDINGS: [KeyBinding; 19] = [
+    KeyBinding {
+        keys: "Up/k",
+        action: "Move up",
+        triggers: &[Trigger::Key(KeyCode::Up), Trigger::Key(KeyCode::Char('k'))],
+        message: Message::CursorUp,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "Down/j",
+        action: "Move down",
+        triggers: &[
+            Trigger::Key(KeyCode::Down),
+            Trigger::Key(KeyCode::Char('j')),
+        ],
+        message: Message::CursorDown,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "Left/h",
+        action: "Previous column",
+        triggers: &[
+            Trigger::Key(KeyCode::Left),
+            Trigger::Key(KeyCode::Char('h')),
+        ],
+        message: Message::ColumnLeft,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "Right/l",
+        action: "Next column",
+        triggers: &[
+            Trigger::Key(KeyCode::Right),
+            Trigger::Key(KeyCode::Char('l')),
+        ],
+        message: Message::ColumnRight,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "PgUp",
+        action: "Page up",
+        triggers: &[Trigger::Key(KeyCode::PageUp)],
+        message: Message::PageUp,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "PgDn",
+        action: "Page down",
+        triggers: &[Trigger::Key(KeyCode::PageDown)],
+        message: Message::PageDown,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "Ctrl-u",
+        action: "Half page up",
+        triggers: &[Trigger::Ctrl('u')],
+        message: Message::HalfPageUp,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "Ctrl-d",
+        action: "Half page down",
+        triggers: &[Trigger::Ctrl('d')],
+        message: Message::HalfPageDown,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "gg",
+        action: "First book",
+        triggers: &[Trigger::Twice('g')],
+        message: Message::Top,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "G",
+        action: "Last book",
+        triggers: &[Trigger::Key(KeyCode::Char('G'))],
+        message: Message::Bottom,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "+",
+        action: "Longer page jump",
+        triggers: &[Trigger::Key(KeyCode::Char('+'))],
+        message: Message::GrowPageJump,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "-",
+        action: "Shorter page jump",
+        triggers: &[Trigger::Key(KeyCode::Char('-'))],
+        message: Message::ShrinkPageJump,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "w",
+        action: "Show want to read",
+        triggers: &[Trigger::Key(KeyCode::Char('w'))],
+        message: Message::ApplyFilter,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "a",
+        action: "Show all",
+        triggers: &[Trigger::Key(KeyCode::Char('a'))],
+        message: Message::ClearFilter,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "o",
+        action: "Open book page",
+        triggers: &[Trigger::Key(KeyCode::Char('o'))],
+        message: Message::OpenBookPage,
+        in_instructions: false,
+    },
+    KeyBinding {
+        keys: "Space",
+        action: "Select",
+        triggers: &[Trigger::Key(KeyCode::Char(' '))],
+        message: Message::ToggleSelected,
+        in_instructions: true,
+    },
+    KeyBinding {
+        keys: "R",
+        action: "Mark read",
+        triggers: &[Trigger::Key(KeyCode::Char('R'))],
+        message: Message::MarkSelectedRead,
+        in_instructions: true,
+    },
+    KeyBinding {
+        keys: "?",
+        action: "Help",
+        triggers: &[Trigger::Key(KeyCode::Char('?'))],
+        message: Message::ToggleHelp,
+        in_instructions: true,
+    },
+    KeyBinding {
+        keys: "Esc",
+        action: "Quit",
+        triggers: &[Trigger::Key(KeyCode::Esc), Trigger::Ctrl('c')],
+        message: Message::Quit,
+        in_instructions: true,
+    },
+];
+
 impl Tui {
-    #[must_use]
-    pub fn new(term_size: Rect) -> anyhow::Result<Self> {
-        let path = Path::new("spine.json");
-        let my_lib = if path.exists() {
-            Library::open(path)?
-        } else {
-            Library::new()
+    /// Creates a TUI over the library at `path`, reloading it when the file changes.
+    ///
+    /// The filter, border and page jump left when the TUI last quit are restored from
+    /// `spine_tui.toml` in the config directory, or beside the library if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the library file exists but can't be opened.
+    pub fn new(path: &Path, term_size: Rect) -> anyhow::Result<Self> {
+        let loaded_modified = modified_time(path);
+        let my_lib = Library::open_optional(path)?.unwrap_or_default();
+        let preferences_path = Preferences::path(Config::default_path().as_deref(), path);
+        let preferences = Preferences::load(&preferences_path);
+
+        let mut tui = Self {
+            path: path.to_owned(),
+            loaded_modified,
+            ..Self::with_library(my_lib, term_size)
         };
-        let all_ids = my_lib.all().map(|b| b.id).collect();
+        match preferences {
+            Ok(preferences) => tui.apply_preferences(&preferences, term_size),
+            Err(e) => {
+                tui.set_status_message(format!("Couldn't read {PREFERENCES_FILE}: {e}"));
+            }
+        }
+        tui.preferences_path = Some(preferences_path);
+        Ok(tui)
+    }
 
-        Ok(Self {
-            library: my_lib,
-            num_visible: term_size.height.saturating_sub(2).into(),
+    /// Creates a TUI over a library that is already loaded, for a terminal of `term_size`.
+    #[must_use]
+    pub fn with_library(library: Library, term_size: Rect) -> Self {
+        let all_ids = active_ids(&library);
+        let mut tui = Self {
+            library,
             filtered: all_ids,
             ..Default::default()
-        })
+        };
+        tui.fit_to(term_size.width, term_size.height);
+        tui
+    }
+
+    /// Draws the list without a border, giving the rows and columns it took up to books.
+    #[must_use]
+    pub fn borderless(self, term_size: Rect) -> Self {
+        self.with_border(Border::Hidden, term_size)
+    }
+
+    /// Draws the list inside a border, even if the preferences say not to.
+    #[must_use]
+    pub fn bordered(self, term_size: Rect) -> Self {
+        self.with_border(Border::Shown, term_size)
     }
 
+    fn with_border(mut self, border: Border, term_size: Rect) -> Self {
+        self.border = border;
+        self.fit_to(term_size.width, term_size.height);
+        self
+    }
+
+    /// Applies preferences saved by an earlier run.
+    fn apply_preferences(&mut self, preferences: &Preferences, term_size: Rect) {
+        self.border = if preferences.borderless {
+            Border::Hidden
+        } else {
+            Border::Shown
+        };
+        self.fit_to(term_size.width, term_size.height);
+        self.page_jump = preferences.page_jump;
+        self.filter = preferences.filter;
+        self.refresh_filtered();
+    }
+
+    /// Returns the preferences to restore next run.
+    fn preferences(&self) -> Preferences {
+        Preferences {
+            filter: self.filter,
+            borderless: self.border == Border::Hidden,
+            page_jump: self.page_jump,
+        }
+    }
+
+    /// Sets the rows and columns of books to fit a terminal `width` by `height`.
+    fn fit_to(&mut self, width: u16, height: u16) {
+        (self.num_visible, self.columns) = self.fitted_size(width, height);
+    }
+
+    /// Returns the rows and columns of books that fit a terminal `width` by `height`, leaving
+    /// room for the border if there is one.
+    fn fitted_size(&self, width: u16, height: u16) -> (usize, usize) {
+        let (chrome_rows, border_width) = match self.border {
+            Border::Shown => (CHROME_ROWS, 2),
+            Border::Hidden => (BORDERLESS_CHROME_ROWS, 0),
+        };
+        (
+            height.saturating_sub(chrome_rows).into(),
+            columns_for_width(width.saturating_sub(border_width)),
+        )
+    }
+
+    /// Shows the TUI in `terminal` until it's quit, then saves any unsaved changes and the view
+    /// preferences.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the terminal can't be drawn on or read from, or the library can't be saved.
     pub fn run(mut self, mut terminal: DefaultTerminal) -> io::Result<()> {
         self.is_running = true;
+        self.dirty = true;
         while self.is_running {
-            terminal.draw(|frame| self.draw(frame))?;
-            if let Some(message) = self.handle_events()? {
+            if self.dirty {
+                terminal.draw(|frame| self.draw(frame))?;
+                self.tick_status_message();
+                self.dirty = false;
+            }
+            if event::poll(FILE_POLL_INTERVAL)?
+                && let Some(message) = self.handle_events()?
+            {
                 self.update(message);
             }
+            self.save_if_idle();
+            self.poll_library_file();
+        }
+        self.save_unsaved().map_err(io::Error::other)?;
+        // Losing the view preferences only warrants a warning, printed once the terminal is
+        // restored so it's still visible after quitting.
+        if let Some(path) = &self.preferences_path
+            && let Err(e) = self.preferences().save(path)
+        {
+            ratatui::restore();
+            eprintln!("Warning: couldn't save {}: {e}", path.display());
         }
         Ok(())
     }
 
+    /// Reloads the library once its file has changed on disk and settled, unless there are
+    /// changes waiting to be saved that reloading would lose.
+    fn poll_library_file(&mut self) {
+        let current = modified_time(&self.path);
+        if current != self.seen_modified.map(|(time, _)| time) {
+            self.seen_modified = current.map(|time| (time, Instant::now()));
+        }
+        let unchanged_for = self
+            .seen_modified
+            .map_or(Duration::ZERO, |(_, seen)| seen.elapsed());
+        if self.last_unsaved_change.is_none()
+            && should_reload(self.loaded_modified, current, unchanged_for)
+        {
+            self.reload(current);
+        }
+    }
+
+    /// Replaces the library with the one on disk, keeping the filter and selected book.
+    fn reload(&mut self, modified: Option<SystemTime>) {
+        self.loaded_modified = modified;
+        self.dirty = true;
+        match Library::open(&self.path) {
+            Ok(library) => {
+                self.library = library;
+                self.selected.retain(|&id| self.library.get(id).is_some());
+                self.refresh_filtered();
+                self.set_status_message("Reloaded library from disk");
+            }
+            Err(e) => self.set_status_message(format!("Couldn't reload library: {e}")),
+        }
+    }
+
     fn draw(&self, frame: &mut Frame) {
         frame.render_widget(self, frame.area());
     }
@@ -73,125 +546,1277 @@ impl Tui {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 Ok(self.handle_key_event(key_event))
             }
-            Event::Resize(_, rows) => Ok(Some(Message::Resize((rows.saturating_sub(2)).into()))),
+            Event::Resize(width, height) => {
+                let (rows, columns) = self.fitted_size(width, height);
+                Ok(Some(Message::Resize { rows, columns }))
+            }
             _ => Ok(None),
         }
     }
 
+    /// Looks the key up in [`KEY_BINDINGS`]. While the help is shown, Esc closes it instead of
+    /// quitting.
     fn handle_key_event(&mut self, key: KeyEvent) -> Option<Message> {
-        match (key.modifiers, key.code) {
-            (_, KeyCode::Esc) => Some(Message::Quit),
-            (_, KeyCode::Up) => Some(Message::CursorUp),
-            (_, KeyCode::Down) => Some(Message::CursorDown),
-            (_, KeyCode::PageUp) => Some(Message::PageUp),
-            (_, KeyCode::PageDown) => Some(Message::PageDown),
-            (_, KeyCode::Char('w')) => Some(Message::ApplyFilter),
-            _ => None,
+        let pending = self.pending_key.take();
+        if self.show_help && key.code == KeyCode::Esc {
+            return Some(Message::ToggleHelp);
         }
+        if let Some(binding) = KEY_BINDINGS
+            .iter()
+            .find(|b| b.triggers.iter().any(|t| t.matches(key, pending)))
+        {
+            return Some(binding.message);
+        }
+        if let KeyCode::Char(c) = key.code
+            && KEY_BINDINGS
+                .iter()
+                .flat_map(|b| b.triggers)
+                .any(|t| matches!(t, Trigger::Twice(first) if *first == c))
+        {
+            self.pending_key = Some(c);
+        }
+        None
     }
 
     fn update(&mut self, msg: Message) {
+        self.dirty = true;
         match msg {
             Message::Quit => self.is_running = false,
-            Message::Resize(rows) => self.num_visible = rows,
+            Message::Resize { rows, columns } => {
+                self.num_visible = rows;
+                self.columns = columns;
+                self.scroll_to_cursor();
+            }
             Message::CursorUp => self.move_cursor_up(),
             Message::CursorDown => self.move_cursor_down(),
+            Message::ColumnLeft => self.move_column_left(),
+            Message::ColumnRight => self.move_column_right(),
             Message::PageUp => self.move_page_up(),
             Message::PageDown => self.move_page_down(),
+            Message::HalfPageUp => self.jump_up(self.num_visible.div_ceil(2)),
+            Message::HalfPageDown => self.jump_down(self.num_visible.div_ceil(2)),
+            Message::Top => self.jump_up(self.filtered.len()),
+            Message::Bottom => self.jump_down(self.filtered.len()),
             Message::ApplyFilter => self.apply_filter(),
+            Message::ClearFilter => self.clear_filter(),
+            Message::OpenBookPage => self.open_book_page(),
+            Message::GrowPageJump => self.set_page_jump(self.page_jump() + 1),
+            Message::ShrinkPageJump => self.set_page_jump(self.page_jump().saturating_sub(1)),
+            Message::ToggleSelected => self.toggle_selected(),
+            Message::MarkSelectedRead => self.mark_selected_read(),
+            Message::ToggleHelp => self.show_help = !self.show_help,
         }
     }
 
     fn move_cursor_up(&mut self) {
-        let is_first_visible = self.cursor == self.scroll_offset;
-        let is_first_overall = self.cursor == 0;
-        if is_first_visible && !is_first_overall {
-            self.scroll_offset -= 1;
-        }
         self.cursor = self.cursor.saturating_sub(1);
+        self.scroll_to_cursor();
     }
 
     fn move_cursor_down(&mut self) {
-        let is_last_visible =
-            self.cursor == (self.scroll_offset + self.num_visible).saturating_sub(1);
-        let is_last_overall = self.cursor == self.filtered.len().saturating_sub(1);
-        if is_last_visible && !is_last_overall {
-            self.scroll_offset += 1;
-        }
         self.cursor = (self.cursor + 1).min(self.filtered.len().saturating_sub(1));
+        self.scroll_to_cursor();
     }
 
-    fn move_page_up(&mut self) {
-        self.scroll_offset = self.scroll_offset.saturating_sub(self.num_visible);
+    /// Moves the cursor to the same row of the previous column, if there is one.
+    fn move_column_left(&mut self) {
+        if let Some(cursor) = self.cursor.checked_sub(self.num_visible.max(1)) {
+            self.cursor = cursor;
+            self.scroll_to_cursor();
+        }
+    }
+
+    /// Moves the cursor to the same row of the next column, or the last book if that column is
+    /// shorter.
+    fn move_column_right(&mut self) {
+        let rows = self.num_visible.max(1);
+        let last = self.filtered.len().saturating_sub(1);
+        if self.cursor / rows < last / rows {
+            self.cursor = (self.cursor + rows).min(last);
+            self.scroll_to_cursor();
+        }
+    }
 
-        self.cursor = self.cursor.saturating_sub(self.num_visible);
+    fn move_page_up(&mut self) {
+        self.jump_up(self.page_jump());
     }
 
     fn move_page_down(&mut self) {
-        let top_next_page = self.scroll_offset + self.num_visible;
-        let top_last_full_page = self.filtered.len().saturating_sub(self.num_visible);
+        self.jump_down(self.page_jump());
+    }
+
+    /// Number of books page up and page down move by, a whole page unless changed.
+    fn page_jump(&self) -> usize {
+        self.page_jump.unwrap_or_else(|| self.page_size())
+    }
+
+    fn set_page_jump(&mut self, books: usize) {
+        let books = books.max(1);
+        self.page_jump = Some(books);
+        self.set_status_message(format!("Page up and down move {books} book(s)"));
+    }
+
+    /// Moves the cursor and the visible rows up by `rows`, stopping at the first book.
+    fn jump_up(&mut self, rows: usize) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(rows);
+
+        self.cursor = self.cursor.saturating_sub(rows);
+        self.scroll_to_cursor();
+    }
+
+    /// Moves the cursor and the visible rows down by `rows`, stopping at the last book.
+    fn jump_down(&mut self, rows: usize) {
+        let unit = self.scroll_unit();
+        let top_next_page = self.scroll_offset + rows;
+        let top_last_full_page = self
+            .filtered
+            .len()
+            .saturating_sub(self.page_size())
+            .div_ceil(unit)
+            * unit;
         self.scroll_offset = top_next_page.min(top_last_full_page);
 
-        let next_page_cursor = self.cursor + self.num_visible;
+        let next_page_cursor = self.cursor + rows;
         self.cursor = next_page_cursor.min(self.filtered.len().saturating_sub(1));
+        self.scroll_to_cursor();
+    }
+
+    /// Number of books visible at once, across all columns.
+    fn page_size(&self) -> usize {
+        (self.num_visible * self.columns.max(1)).max(1)
+    }
+
+    /// Number of books the list scrolls by: a whole column when there are several, so books
+    /// don't move between rows as it scrolls.
+    fn scroll_unit(&self) -> usize {
+        if self.columns > 1 {
+            self.num_visible.max(1)
+        } else {
+            1
+        }
+    }
+
+    /// Scrolls as little as needed to show the cursor, keeping the first visible book at the top
+    /// of a column.
+    fn scroll_to_cursor(&mut self) {
+        let unit = self.scroll_unit();
+        let page = self.page_size();
+        self.scroll_offset = if self.cursor < self.scroll_offset {
+            self.cursor / unit * unit
+        } else if self.cursor >= self.scroll_offset + page {
+            (self.cursor + 1 - page).div_ceil(unit) * unit
+        } else {
+            self.scroll_offset / unit * unit
+        };
+    }
+
+    /// Returns the column and row a book is drawn at, if it is visible.
+    fn grid_position(&self, index: usize) -> Option<(usize, usize)> {
+        let offset = index.checked_sub(self.scroll_offset)?;
+        let rows = self.num_visible.max(1);
+        (offset < self.page_size()).then_some((offset / rows, offset % rows))
     }
 
     fn apply_filter(&mut self) {
+        let filtered = self.want_ids();
+        self.set_status_message(format!(
+            "Showing {} book(s) you want to read",
+            filtered.len()
+        ));
+        self.filter = Some(Filter::Want);
+        self.set_filtered(filtered);
+    }
+
+    /// Returns the ids of the books the user wants to read.
+    fn want_ids(&self) -> Vec<Uuid> {
         let filter = LibrarySearch {
-            status: Some(Status::Want),
+            status: Some(vec![Status::Want]),
             ..Default::default()
         };
-        self.filtered = self.library.search(&filter).map(|b| b.id).collect();
-        self.cursor = 0;
-        self.scroll_offset = 0;
+        self.library.search(&filter).map(|b| b.id).collect()
+    }
+
+    fn clear_filter(&mut self) {
+        let all_ids = active_ids(&self.library);
+        self.filter = None;
+        self.set_filtered(all_ids);
+        self.set_status_message("Showing all books");
+    }
+
+    /// Opens the selected book's Open Library page in the browser, if it has an ISBN.
+    fn open_book_page(&mut self) {
+        let Some(book) = self
+            .filtered
+            .get(self.cursor)
+            .and_then(|&id| self.library.get(id))
+        else {
+            return;
+        };
+        let Some(isbn) = &book.isbn else {
+            let message = format!("No ISBN for {}, can't open its page", book.title);
+            self.set_status_message(message);
+            return;
+        };
+        let url = book_page_url(isbn);
+        match open::that_detached(&url) {
+            Ok(()) => self.set_status_message(format!("Opened {url}")),
+            Err(e) => self.set_status_message(format!("Couldn't open {url}: {e}")),
+        }
+    }
+
+    /// Ticks the book under the cursor for a batch action, or unticks it if it is ticked.
+    fn toggle_selected(&mut self) {
+        let Some(&id) = self.filtered.get(self.cursor) else {
+            return;
+        };
+        if !self.selected.remove(&id) {
+            self.selected.insert(id);
+        }
+    }
+
+    /// Marks every ticked book as read and saves the library, then clears the ticks.
+    ///
+    /// If a book can't be marked, it and the books not yet reached stay ticked.
+    fn mark_selected_read(&mut self) {
+        if self.selected.is_empty() {
+            self.set_status_message("No books selected, tick some with <Space>");
+            return;
+        }
+        let mut changed = 0;
+        let mut error = None;
+        for id in mem::take(&mut self.selected) {
+            if error.is_some() {
+                self.selected.insert(id);
+                continue;
+            }
+            match self.library.update_status(id, Status::Read, &SystemClock) {
+                Ok(true) => changed += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    self.selected.insert(id);
+                    error = Some(e);
+                }
+            }
+        }
+        self.refresh_filtered();
+        if changed > 0 {
+            self.last_unsaved_change = Some(Instant::now());
+        }
+        self.set_status_message(error.map_or_else(
+            || format!("Marked {changed} book(s) read"),
+            |e| format!("Marked {changed} book(s) read, couldn't mark the rest: {e}"),
+        ));
+    }
+
+    /// Saves any unsaved changes once the library has gone [`SAVE_DEBOUNCE`] without another.
+    fn save_if_idle(&mut self) {
+        if should_save(self.last_unsaved_change.map(|changed| changed.elapsed()))
+            && let Err(e) = self.save_unsaved()
+        {
+            self.set_status_message(format!("Couldn't save library: {e}"));
+            self.dirty = true;
+        }
+    }
+
+    /// Saves the library now if it has unsaved changes, which stay pending if saving fails.
+    fn save_unsaved(&mut self) -> Result<(), SpineError> {
+        if self.last_unsaved_change.is_some() {
+            self.save()?;
+            self.last_unsaved_change = None;
+        }
+        Ok(())
+    }
+
+    /// Saves the library to the file it was loaded from, without reloading it afterwards.
+    fn save(&mut self) -> Result<(), SpineError> {
+        let _lock = LibraryLock::acquire(&self.path, LOCK_TIMEOUT)?;
+        self.library.save(&self.path)?;
+        self.loaded_modified = modified_time(&self.path);
+        Ok(())
+    }
+
+    fn set_status_message(&mut self, message: impl Into<String>) {
+        self.status_message = Some(message.into());
+        self.status_ttl = STATUS_MESSAGE_REDRAWS;
+    }
+
+    /// Counts down the redraws left for the status message, clearing it when none are left.
+    fn tick_status_message(&mut self) {
+        if self.status_ttl == 0 {
+            self.status_message = None;
+        } else {
+            self.status_ttl -= 1;
+        }
+    }
+
+    /// Lists the books matching the current filter again, e.g. after they have changed.
+    fn refresh_filtered(&mut self) {
+        let ids = match self.filter {
+            Some(Filter::Want) => self.want_ids(),
+            None => active_ids(&self.library),
+        };
+        self.set_filtered(ids);
+    }
+
+    /// Replaces the listed books, keeping the cursor on the selected book if it is still listed.
+    fn set_filtered(&mut self, filtered: Vec<Uuid>) {
+        let selected = self.filtered.get(self.cursor).copied();
+        self.filtered = filtered;
+        self.cursor = selected
+            .and_then(|id| self.filtered.iter().position(|&f| f == id))
+            .unwrap_or(self.cursor)
+            .min(self.filtered.len().saturating_sub(1));
+        self.scroll_to_cursor();
+    }
+}
+
+/// Returns the ids of the books that aren't archived.
+fn active_ids(library: &Library) -> Vec<Uuid> {
+    library
+        .search(&LibrarySearch::default())
+        .map(|b| b.id)
+        .collect()
+}
+
+/// Returns when the file at `path` was last modified, if it exists.
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Decides whether to reload the library file, given its modification time when it was loaded,
+/// its modification time now, and how long it has had that modification time.
+fn should_reload(
+    loaded: Option<SystemTime>,
+    current: Option<SystemTime>,
+    unchanged_for: Duration,
+) -> bool {
+    current.is_some() && current != loaded && unchanged_for >= RELOAD_DEBOUNCE
+}
+
+/// Decides whether to save the library, given how long ago it was last changed if it has
+/// unsaved changes.
+fn should_save(since_last_change: Option<Duration>) -> bool {
+    since_last_change.is_some_and(|idle| idle >= SAVE_DEBOUNCE)
+}
+
+/// Returns the Open Library page for the book with this ISBN.
+fn book_page_url(isbn: &Isbn) -> String {
+    format!(
+        "https://openlibrary.org/isbn/{}",
+        Isbn::normalise(isbn.as_str())
+    )
+}
+
+/// Returns the rating and reading progress of a book, e.g. "★★★★☆ ▓▓▓░░ 60%", leaving out
+/// whichever it doesn't have. The result is never wider than 16 characters.
+fn book_extras(book: &Book) -> Option<String> {
+    let stars = book.rating.map(|rating| {
+        let rating = rating.min(MAX_RATING);
+        "★".repeat(rating.into()) + &"☆".repeat((MAX_RATING - rating).into())
+    });
+    let progress = book.progress_percent().map(|percent| {
+        let filled = u16::from(percent) * u16::from(PROGRESS_BAR_CELLS) / 100;
+        format!(
+            "{}{} {percent}%",
+            "▓".repeat(filled.into()),
+            "░".repeat((u16::from(PROGRESS_BAR_CELLS) - filled).into())
+        )
+    });
+    match (stars, progress) {
+        (Some(stars), Some(progress)) => Some(format!("{stars} {progress}")),
+        (stars, progress) => stars.or(progress),
     }
 }
 
+/// Shortens `text` to fit in `width` cells, ending it with "…" if anything was cut.
+fn truncate_to_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_owned();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if used + char_width >= width {
+            break;
+        }
+        used += char_width;
+        truncated.push(c);
+    }
+    if width > 0 {
+        truncated.push('…');
+    }
+    truncated
+}
+
+/// Returns how many columns of books fit in a list `width` wide.
+fn columns_for_width(width: u16) -> usize {
+    usize::from((width / MIN_COLUMN_WIDTH).max(1))
+}
+
 impl Widget for &Tui {
     fn render(self, area: Rect, buf: &mut Buffer)
     where
         Self: Sized,
     {
         let title = Line::from(" Spine - Your Books ".bold());
-        let instructions = Line::from(vec![
-            " Move up ".into(),
-            "<Up>".blue().bold(),
-            " Move down ".into(),
-            "<Down>".blue().bold(),
-            " Quit ".into(),
-            "<Esc> ".blue().bold(),
-        ]);
-        let block = Block::bordered()
-            .title(title.centered())
-            .title_bottom(instructions.centered())
-            .border_set(border::THICK);
+        let mut instructions = Line::default();
+        for binding in KEY_BINDINGS.iter().filter(|b| b.in_instructions) {
+            instructions.push_span(format!(" {} ", binding.action));
+            instructions.push_span(format!("<{}>", binding.keys).blue().bold());
+        }
+        instructions.push_span(" ");
+        let [list_area, message_area] = if self.border == Border::Hidden {
+            let [list_area, message_area, instructions_area] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .areas(area);
+            instructions.centered().render(instructions_area, buf);
+            [list_area, message_area]
+        } else {
+            let block = Block::bordered()
+                .title(title.centered())
+                .title_bottom(instructions.centered())
+                .border_set(border::THICK);
+            let areas = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)])
+                .areas(block.inner(area));
+            block.render(area, buf);
+            areas
+        };
 
+        let columns = self.columns.max(1);
+        let column_areas = Layout::horizontal(vec![Constraint::Fill(1); columns]).split(list_area);
         let filtered_set: HashSet<Uuid> = self.filtered.iter().copied().collect();
-        let books = self
+        let mut column_items = vec![Vec::new(); columns];
+        for (i, b) in self
             .library
             .all()
             .filter(|b| filtered_set.contains(&b.id))
             .enumerate()
-            .skip(self.scroll_offset)
-            .take(usize::from(area.height))
-            .map(|(i, b)| (i, ListItem::from(b.to_string())))
-            .map(|(i, t)| if i == self.cursor { t.green() } else { t })
-            .collect::<List>();
+        {
+            let Some((column, row)) = self.grid_position(i) else {
+                continue;
+            };
+            if row >= usize::from(list_area.height) {
+                continue;
+            }
+            let mut text = if self.selected.contains(&b.id) {
+                format!("* {b}")
+            } else {
+                b.to_string()
+            };
+            if i == self.cursor
+                && let Some(extras) = book_extras(b)
+            {
+                text = format!("{text}  {extras}");
+            }
+            let text = truncate_to_width(&text, column_areas[column].width.into());
+            let item = ListItem::from(text).style(status_style(b.status));
+            column_items[column].push(if i == self.cursor {
+                item.reversed()
+            } else {
+                item
+            });
+        }
+
+        for (items, area) in column_items.into_iter().zip(column_areas.iter()) {
+            List::new(items).render(*area, buf);
+        }
+        let total = self.library.all().filter(|b| !b.archived).count();
+        let count = (self.filtered.len() != total).then(|| {
+            let name = self.filter.map_or("filtered", Filter::name);
+            format!("{name}: showing {} / {total} ", self.filtered.len())
+        });
+        let count_width = count.as_deref().map_or(0, |c| Line::from(c).width());
+        let [message_area, count_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(u16::try_from(count_width).unwrap_or(u16::MAX)),
+        ])
+        .areas(message_area);
+        if let Some(message) = &self.status_message {
+            Line::from(message.as_str().italic()).render(message_area, buf);
+        }
+        if let Some(count) = count {
+            Line::from(count.bold()).render(count_area, buf);
+        }
+        if self.show_help {
+            render_help(area, buf);
+        }
+    }
+}
+
+/// Draws every key binding in a box over the middle of `area`.
+fn render_help(area: Rect, buf: &mut Buffer) {
+    let keys_width = KEY_BINDINGS
+        .iter()
+        .map(|b| b.keys.len())
+        .max()
+        .unwrap_or_default();
+    let lines: Vec<Line> = KEY_BINDINGS
+        .iter()
+        .map(|b| {
+            Line::from(vec![
+                format!(" {:>keys_width$} ", b.keys).blue().bold(),
+                format!(" {} ", b.action).into(),
+            ])
+        })
+        .collect();
+    let width = lines.iter().map(Line::width).max().unwrap_or_default() + 2;
+    let height = lines.len() + 2;
+    let help_area = area.centered(
+        Constraint::Length(u16::try_from(width).unwrap_or(u16::MAX)),
+        Constraint::Length(u16::try_from(height).unwrap_or(u16::MAX)),
+    );
+    let block = Block::bordered()
+        .title(Line::from(" Keys ".bold()).centered())
+        .title_bottom(Line::from(" Close <?/Esc> ").centered())
+        .border_set(border::THICK);
+    Clear.render(help_area, buf);
+    List::new(lines).block(block).render(help_area, buf);
+}
 
-        books.block(block).render(area, buf);
+/// Colours a list item by the book's status.
+const fn status_style(status: Status) -> Style {
+    match status {
+        Status::Want => Style::new().fg(Color::Yellow),
+        Status::Reading => Style::new().fg(Color::Blue),
+        Status::Read => Style::new().fg(Color::Green).dim(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Author, Book, lock::lock_path};
+    use ratatui::style::Modifier;
+    use std::str::FromStr;
+
+    fn tui_with_books(books: &[(&str, Status)]) -> Tui {
+        let mut library = Library::new();
+        for (title, status) in books {
+            library.add(Book {
+                title: (*title).to_owned(),
+                author: Author::from_str("george orwell").unwrap(),
+                status: *status,
+                ..Default::default()
+            });
+        }
+        let all_ids = library.all().map(|b| b.id).collect();
+
+        Tui {
+            library,
+            num_visible: 10,
+            columns: 1,
+            filtered: all_ids,
+            ..Default::default()
+        }
+    }
 
     #[test]
     fn handle_key_event_quits_on_esc() {
+        let tmp_dir = tempfile::tempdir().unwrap();
         let term_size = Rect::new(1, 2, 3, 4);
-        let mut tui = Tui::new(term_size).unwrap();
+        let mut tui = Tui::new(&tmp_dir.path().join("spine.json"), term_size).unwrap();
         tui.handle_key_event(KeyCode::Esc.into());
 
         assert!(!tui.is_running);
     }
+
+    #[test]
+    fn new_opens_library_at_path_and_preferences_in_config_dir() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("work.json");
+        tui_with_books(&[("kim", Status::Want)])
+            .library
+            .save(&path)
+            .unwrap();
+
+        let tui = Tui::new(&path, Rect::new(0, 0, 80, 24)).unwrap();
+
+        assert_eq!(tui.library.all().count(), 1);
+        assert_eq!(
+            tui.preferences_path,
+            Some(Preferences::path(Config::default_path().as_deref(), &path))
+        );
+    }
+
+    #[test]
+    fn preferences_are_kept_beside_config_file() {
+        let path = Preferences::path(
+            Some(Path::new("/home/me/.config/spine/spine.toml")),
+            Path::new("/books/work.json"),
+        );
+
+        assert_eq!(
+            path,
+            Path::new("/home/me/.config/spine").join(PREFERENCES_FILE)
+        );
+    }
+
+    #[test]
+    fn preferences_are_kept_beside_library_without_config_dir() {
+        let path = Preferences::path(None, Path::new("/books/work.json"));
+
+        assert_eq!(path, Path::new("/books").join(PREFERENCES_FILE));
+    }
+
+    #[test]
+    fn with_library_lists_active_books_of_in_memory_library() {
+        let mut library = Library::new();
+        for (title, archived) in [("burmese days", false), ("animal farm", true)] {
+            library.add(Book {
+                title: title.to_owned(),
+                author: Author::from_str("george orwell").unwrap(),
+                archived,
+                ..Default::default()
+            });
+        }
+
+        let tui = Tui::with_library(library, Rect::new(0, 0, 120, 13));
+
+        assert_eq!(tui.filtered.len(), 1);
+        assert_eq!((tui.num_visible, tui.columns), (10, 2));
+        assert_eq!(tui.path, PathBuf::new());
+    }
+
+    #[test]
+    fn handle_key_event_quits_on_ctrl_c() {
+        let mut tui = tui_with_books(&[]);
+
+        let msg = tui.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+
+        assert!(matches!(msg, Some(Message::Quit)));
+    }
+
+    fn press(tui: &mut Tui, key: KeyEvent) {
+        if let Some(msg) = tui.handle_key_event(key) {
+            tui.update(msg);
+        }
+    }
+
+    #[test]
+    fn unbound_key_leaves_tui_clean() {
+        let mut tui = tui_with_books(&[("burmese days", Status::Want)]);
+
+        press(&mut tui, KeyCode::Char('x').into());
+
+        assert!(!tui.dirty);
+    }
+
+    #[test]
+    fn navigation_key_marks_tui_dirty() {
+        let mut tui = tui_with_books(&[("burmese days", Status::Want)]);
+
+        press(&mut tui, KeyCode::Char('j').into());
+
+        assert!(tui.dirty);
+    }
+
+    #[test]
+    fn handle_key_event_moves_cursor_with_j_and_k() {
+        let mut tui = tui_with_books(&[
+            ("burmese days", Status::Want),
+            ("animal farm", Status::Read),
+            ("coming up for air", Status::Want),
+        ]);
+
+        press(&mut tui, KeyCode::Char('j').into());
+        press(&mut tui, KeyCode::Char('j').into());
+        assert_eq!(tui.cursor, 2);
+
+        press(&mut tui, KeyCode::Char('k').into());
+        assert_eq!(tui.cursor, 1);
+    }
+
+    #[test]
+    fn handle_key_event_jumps_to_top_on_g_g() {
+        let titles: Vec<String> = (0..25).map(|i| format!("book {i}")).collect();
+        let books: Vec<(&str, Status)> =
+            titles.iter().map(|t| (t.as_str(), Status::Want)).collect();
+        let mut tui = tui_with_books(&books);
+        press(&mut tui, KeyCode::Char('G').into());
+        assert_eq!((tui.cursor, tui.scroll_offset), (24, 15));
+
+        press(&mut tui, KeyCode::Char('g').into());
+        assert_eq!(tui.cursor, 24);
+        press(&mut tui, KeyCode::Char('g').into());
+
+        assert_eq!((tui.cursor, tui.scroll_offset), (0, 0));
+    }
+
+    fn tui_with_columns(num_books: usize, rows: usize, columns: usize) -> Tui {
+        let titles: Vec<String> = (0..num_books).map(|i| format!("book {i}")).collect();
+        let books: Vec<(&str, Status)> =
+            titles.iter().map(|t| (t.as_str(), Status::Want)).collect();
+        Tui {
+            num_visible: rows,
+            columns,
+            ..tui_with_books(&books)
+        }
+    }
+
+    #[test]
+    fn shrinking_page_jump_shortens_page_down() {
+        let mut tui = tui_with_columns(20, 5, 1);
+
+        press(&mut tui, KeyCode::Char('-').into());
+        press(&mut tui, KeyCode::Char('-').into());
+        press(&mut tui, KeyCode::PageDown.into());
+
+        assert_eq!(tui.cursor, 3);
+    }
+
+    #[test]
+    fn growing_page_jump_lengthens_page_up() {
+        let mut tui = tui_with_columns(20, 5, 1);
+        tui.cursor = 19;
+
+        press(&mut tui, KeyCode::Char('+').into());
+        press(&mut tui, KeyCode::PageUp.into());
+
+        assert_eq!(tui.cursor, 13);
+    }
+
+    #[test]
+    fn page_jump_is_at_least_one_book() {
+        let mut tui = tui_with_columns(20, 2, 1);
+
+        for _ in 0..5 {
+            press(&mut tui, KeyCode::Char('-').into());
+        }
+        press(&mut tui, KeyCode::PageDown.into());
+
+        assert_eq!(tui.page_jump, Some(1));
+        assert_eq!(tui.cursor, 1);
+    }
+
+    #[test]
+    fn mark_selected_read_updates_ticked_books_and_clears_selection() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let mut tui = Tui {
+            path: tmp_dir.path().join("spine.json"),
+            ..tui_with_books(&[
+                ("burmese days", Status::Want),
+                ("animal farm", Status::Reading),
+                ("coming up for air", Status::Want),
+            ])
+        };
+
+        press(&mut tui, KeyCode::Char(' ').into());
+        press(&mut tui, KeyCode::Char('j').into());
+        press(&mut tui, KeyCode::Char('j').into());
+        press(&mut tui, KeyCode::Char(' ').into());
+        assert_eq!(tui.selected.len(), 2);
+        press(&mut tui, KeyCode::Char('R').into());
+
+        let statuses: Vec<_> = tui.library.all().map(|b| b.status).collect();
+        assert_eq!(statuses, [Status::Read, Status::Reading, Status::Read]);
+        assert!(tui.selected.is_empty());
+        tui.save_unsaved().unwrap();
+        let saved = Library::open(&tui.path).unwrap();
+        assert_eq!(saved.all().filter(|b| b.status == Status::Read).count(), 2);
+    }
+
+    #[test]
+    fn mark_selected_read_keeps_unmarked_books_ticked_after_error() {
+        let mut tui = tui_with_books(&[("burmese days", Status::Want)]);
+        let id = tui.filtered[0];
+        let missing = Uuid::new_v4();
+        tui.selected.extend([id, missing]);
+
+        press(&mut tui, KeyCode::Char('R').into());
+
+        let marked = tui.library.get(id).unwrap().status == Status::Read;
+        assert!(tui.selected.contains(&missing));
+        assert_ne!(marked, tui.selected.contains(&id));
+        assert_eq!(marked, tui.last_unsaved_change.is_some());
+    }
+
+    #[test]
+    fn marking_read_saves_once_changes_stop_for_debounce() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let mut tui = Tui {
+            path: tmp_dir.path().join("spine.json"),
+            ..tui_with_books(&[("burmese days", Status::Want), ("kim", Status::Want)])
+        };
+
+        press(&mut tui, KeyCode::Char(' ').into());
+        press(&mut tui, KeyCode::Char('R').into());
+        press(&mut tui, KeyCode::Char('j').into());
+        press(&mut tui, KeyCode::Char(' ').into());
+        press(&mut tui, KeyCode::Char('R').into());
+        tui.save_if_idle();
+
+        assert!(tui.last_unsaved_change.is_some());
+        assert!(!tui.path.exists());
+
+        tui.last_unsaved_change = Instant::now().checked_sub(SAVE_DEBOUNCE);
+        tui.save_if_idle();
+
+        assert_eq!(tui.last_unsaved_change, None);
+        let saved = Library::open(&tui.path).unwrap();
+        assert!(saved.all().all(|b| b.status == Status::Read));
+    }
+
+    #[test]
+    fn failed_save_keeps_changes_from_being_reloaded_over() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let mut tui = Tui {
+            path: tmp_dir.path().join("spine.json"),
+            ..tui_with_books(&[("burmese days", Status::Want)])
+        };
+        tui.library.save(&tui.path).unwrap();
+        // A directory where the lock file should be makes saving fail.
+        fs::create_dir(lock_path(&tui.path)).unwrap();
+
+        press(&mut tui, KeyCode::Char(' ').into());
+        press(&mut tui, KeyCode::Char('R').into());
+        tui.last_unsaved_change = Instant::now().checked_sub(SAVE_DEBOUNCE);
+        tui.save_if_idle();
+        tui.poll_library_file();
+        tui.seen_modified = tui
+            .seen_modified
+            .map(|(time, seen)| (time, seen.checked_sub(RELOAD_DEBOUNCE).unwrap()));
+        tui.poll_library_file();
+
+        assert!(tui.last_unsaved_change.is_some());
+        assert_eq!(tui.library.all().next().unwrap().status, Status::Read);
+    }
+
+    #[test]
+    fn should_save_only_unsaved_changes_after_debounce() {
+        assert!(should_save(Some(SAVE_DEBOUNCE)));
+        assert!(!should_save(Some(SAVE_DEBOUNCE / 2)));
+        assert!(!should_save(None));
+    }
+
+    #[test]
+    fn columns_for_width_falls_back_to_one_column_when_narrow() {
+        assert_eq!(columns_for_width(80), 1);
+        assert_eq!(columns_for_width(2 * MIN_COLUMN_WIDTH), 2);
+        assert_eq!(columns_for_width(3 * MIN_COLUMN_WIDTH + 8), 3);
+    }
+
+    #[test]
+    fn borderless_shows_more_rows_for_same_terminal() {
+        let term_size = Rect::new(0, 0, 2 * MIN_COLUMN_WIDTH, 12);
+        let bordered = Tui::with_library(Library::new(), term_size);
+
+        let borderless = Tui::with_library(Library::new(), term_size).borderless(term_size);
+
+        assert_eq!((bordered.num_visible, bordered.columns), (9, 1));
+        assert_eq!((borderless.num_visible, borderless.columns), (10, 2));
+    }
+
+    #[test]
+    fn preferences_round_trip_through_file() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join(PREFERENCES_FILE);
+        let preferences = Preferences {
+            filter: Some(Filter::Want),
+            borderless: true,
+            page_jump: Some(4),
+        };
+
+        preferences.save(&path).unwrap();
+
+        assert_eq!(Preferences::load(&path).unwrap(), preferences);
+    }
+
+    #[test]
+    fn preferences_default_when_file_absent() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+
+        let preferences = Preferences::load(&tmp_dir.path().join(PREFERENCES_FILE)).unwrap();
+
+        assert_eq!(preferences, Preferences::default());
+    }
+
+    #[test]
+    fn apply_preferences_restores_filter_border_and_page_jump() {
+        let term_size = Rect::new(0, 0, 80, 12);
+        let mut tui = tui_with_books(&[
+            ("burmese days", Status::Want),
+            ("animal farm", Status::Read),
+        ]);
+        let preferences = Preferences {
+            filter: Some(Filter::Want),
+            borderless: true,
+            page_jump: Some(4),
+        };
+
+        tui.apply_preferences(&preferences, term_size);
+
+        assert_eq!(tui.filtered.len(), 1);
+        assert_eq!(tui.num_visible, 10);
+        assert_eq!(tui.page_jump(), 4);
+        assert_eq!(tui.preferences(), preferences);
+    }
+
+    /// Returns each row of the buffer as text.
+    fn rows(buf: &Buffer) -> Vec<String> {
+        let area = buf.area;
+        (area.top()..area.bottom())
+            .map(|y| {
+                (area.left()..area.right())
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_key_binding_is_listed_in_instructions_or_help() {
+        let mut tui = tui_with_books(&[("burmese days", Status::Want)]);
+        tui.show_help = true;
+        let area = Rect::new(0, 0, 120, 30);
+        let mut buf = Buffer::empty(area);
+
+        (&tui).render(area, &mut buf);
+
+        let rows = rows(&buf);
+        let instructions = &rows[rows.len() - 1];
+        for binding in &KEY_BINDINGS {
+            let in_instructions =
+                instructions.contains(&format!(" {} <{}>", binding.action, binding.keys));
+            let in_help = rows
+                .iter()
+                .any(|row| row.contains(&format!(" {}  {} ", binding.keys, binding.action)));
+            assert!(in_help, "{} missing from help", binding.action);
+            assert_eq!(
+                in_instructions, binding.in_instructions,
+                "{} in instructions: {instructions}",
+                binding.action
+            );
+        }
+    }
+
+    #[test]
+    fn every_trigger_sends_its_bindings_message() {
+        for binding in &KEY_BINDINGS {
+            for trigger in binding.triggers {
+                let mut tui = tui_with_books(&[]);
+                let msg = match *trigger {
+                    Trigger::Key(code) => tui.handle_key_event(code.into()),
+                    Trigger::Ctrl(c) => {
+                        tui.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
+                    }
+                    Trigger::Twice(c) => {
+                        assert_eq!(tui.handle_key_event(KeyCode::Char(c).into()), None);
+                        tui.handle_key_event(KeyCode::Char(c).into())
+                    }
+                };
+
+                assert_eq!(msg, Some(binding.message), "{trigger:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn esc_closes_help_instead_of_quitting() {
+        let mut tui = tui_with_books(&[("burmese days", Status::Want)]);
+        tui.is_running = true;
+
+        press(&mut tui, KeyCode::Char('?').into());
+        assert!(tui.show_help);
+        press(&mut tui, KeyCode::Esc.into());
+
+        assert!(!tui.show_help);
+        assert!(tui.is_running);
+    }
+
+    #[test]
+    fn render_borderless_starts_list_at_top_left() {
+        let mut tui = tui_with_books(&[("burmese days", Status::Want)]);
+        tui.border = Border::Hidden;
+        let area = Rect::new(0, 0, 100, 8);
+        let mut buf = Buffer::empty(area);
+
+        (&tui).render(area, &mut buf);
+
+        assert_eq!(buf[(0, 0)].symbol(), "b");
+        let bottom_row: String = (0..area.width).map(|x| buf[(x, 7)].symbol()).collect();
+        assert!(bottom_row.contains("Quit"), "{bottom_row}");
+    }
+
+    #[test]
+    fn render_lays_out_wide_and_combining_titles_by_display_width() {
+        let mut library = Library::new();
+        for (title, author) in [
+            ("ノルウェイの森", "村上 春樹"),
+            ("Cafe\u{301}", "zoë heller"),
+        ] {
+            library.add(Book {
+                title: title.to_owned(),
+                author: Author::from_str(author).unwrap(),
+                ..Default::default()
+            });
+        }
+        let area = Rect::new(0, 0, 60, 5);
+        let tui = Tui::with_library(library, area);
+        let mut buf = Buffer::empty(area);
+
+        (&tui).render(area, &mut buf);
+
+        // Each CJK character takes two cells, and the combining accent none.
+        assert_eq!(buf[(1, 1)].symbol(), "ノ");
+        assert_eq!(buf[(13, 1)].symbol(), "森");
+        assert_eq!(buf[(15, 1)].symbol(), ",");
+        assert_eq!(buf[(4, 2)].symbol(), "e\u{301}");
+        assert_eq!(buf[(5, 2)].symbol(), ",");
+    }
+
+    #[test]
+    fn render_truncates_overlong_title_with_ellipsis_inside_border() {
+        let tui = tui_with_books(&[("a".repeat(200).as_str(), Status::Want)]);
+        let area = Rect::new(0, 0, 40, 5);
+        let mut buf = Buffer::empty(area);
+
+        (&tui).render(area, &mut buf);
+
+        let rows = rows(&buf);
+        assert_eq!(rows[1], format!("┃{}…┃", "a".repeat(37)));
+        for row in &rows[1..4] {
+            assert!(row.starts_with('┃') && row.ends_with('┃'), "{row}");
+        }
+    }
+
+    #[test]
+    fn truncate_to_width_counts_wide_characters_and_leaves_short_text() {
+        assert_eq!(truncate_to_width("kim", 3), "kim");
+        assert_eq!(truncate_to_width("burmese days", 6), "burme…");
+        assert_eq!(truncate_to_width("ノルウェイの森", 6), "ノル…");
+        assert_eq!(truncate_to_width("kim", 0), "");
+    }
+
+    #[test]
+    fn grid_position_flows_books_down_each_column() {
+        let tui = tui_with_columns(10, 3, 2);
+
+        assert_eq!(tui.grid_position(0), Some((0, 0)));
+        assert_eq!(tui.grid_position(2), Some((0, 2)));
+        assert_eq!(tui.grid_position(3), Some((1, 0)));
+        assert_eq!(tui.grid_position(5), Some((1, 2)));
+        assert_eq!(tui.grid_position(6), None);
+    }
+
+    #[test]
+    fn cursor_moves_between_columns() {
+        let mut tui = tui_with_columns(10, 3, 2);
+
+        press(&mut tui, KeyCode::Char('l').into());
+        assert_eq!(tui.grid_position(tui.cursor), Some((1, 0)));
+
+        press(&mut tui, KeyCode::Char('j').into());
+        press(&mut tui, KeyCode::Char('j').into());
+        press(&mut tui, KeyCode::Char('j').into());
+        assert_eq!(tui.cursor, 6);
+        assert_eq!(tui.scroll_offset, 3);
+        assert_eq!(tui.grid_position(tui.cursor), Some((1, 0)));
+
+        press(&mut tui, KeyCode::Char('h').into());
+        assert_eq!(tui.cursor, 3);
+        assert_eq!(tui.grid_position(tui.cursor), Some((0, 0)));
+    }
+
+    #[test]
+    fn cursor_moves_right_to_last_book_in_shorter_column() {
+        let mut tui = tui_with_columns(5, 3, 2);
+        press(&mut tui, KeyCode::Char('j').into());
+        press(&mut tui, KeyCode::Char('j').into());
+
+        press(&mut tui, KeyCode::Char('l').into());
+
+        assert_eq!(tui.cursor, 4);
+    }
+
+    #[test]
+    fn render_colours_want_book_yellow() {
+        let tui = tui_with_books(&[
+            ("animal farm", Status::Read),
+            ("burmese days", Status::Want),
+        ]);
+        let area = Rect::new(0, 0, 30, 5);
+        let mut buf = Buffer::empty(area);
+
+        (&tui).render(area, &mut buf);
+
+        let want_row = &buf[(1, 2)];
+        assert_eq!(want_row.symbol(), "b");
+        assert_eq!(want_row.fg, Color::Yellow);
+        assert!(!want_row.modifier.contains(Modifier::REVERSED));
+        assert!(buf[(1, 1)].modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn render_shows_count_when_filter_applied() {
+        let mut tui = tui_with_books(&[
+            ("burmese days", Status::Want),
+            ("animal farm", Status::Read),
+            ("coming up for air", Status::Read),
+        ]);
+        let area = Rect::new(0, 0, 60, 8);
+        press(&mut tui, KeyCode::Char('w').into());
+        let mut buf = Buffer::empty(area);
+
+        (&tui).render(area, &mut buf);
+
+        let message_row: String = (0..area.width).map(|x| buf[(x, 6)].symbol()).collect();
+        assert!(message_row.contains("want: showing 1 / 3"), "{message_row}");
+    }
+
+    #[test]
+    fn render_shows_rating_and_progress_of_book_under_cursor() {
+        let mut tui = tui_with_books(&[("kim", Status::Reading), ("emma", Status::Reading)]);
+        for id in tui.filtered.clone() {
+            let book = tui.library.get_mut(id).unwrap();
+            book.rating = Some(4);
+            (book.page, book.pages) = (Some(60), Some(100));
+        }
+        let area = Rect::new(0, 0, 60, 8);
+        let mut buf = Buffer::empty(area);
+
+        (&tui).render(area, &mut buf);
+
+        let row = |y| -> String { (0..area.width).map(|x| buf[(x, y)].symbol()).collect() };
+        assert!(
+            row(1).contains("kim, george orwell  ★★★★☆ ▓▓▓░░ 60%"),
+            "{}",
+            row(1)
+        );
+        assert!(!row(2).contains('★'), "{}", row(2));
+    }
+
+    #[test]
+    fn book_extras_leaves_out_missing_rating_or_progress() {
+        let rated = Book {
+            rating: Some(2),
+            ..Default::default()
+        };
+        let started = Book {
+            page: Some(1),
+            pages: Some(3),
+            ..Default::default()
+        };
+
+        assert_eq!(book_extras(&rated).as_deref(), Some("★★☆☆☆"));
+        assert_eq!(book_extras(&started).as_deref(), Some("▓░░░░ 33%"));
+        assert_eq!(book_extras(&Book::default()), None);
+    }
+
+    #[test]
+    fn render_hides_count_without_filter() {
+        let tui = tui_with_books(&[("burmese days", Status::Want)]);
+        let area = Rect::new(0, 0, 60, 8);
+        let mut buf = Buffer::empty(area);
+
+        (&tui).render(area, &mut buf);
+
+        let message_row: String = (0..area.width).map(|x| buf[(x, 6)].symbol()).collect();
+        assert!(!message_row.contains("showing"), "{message_row}");
+    }
+
+    #[test]
+    fn should_reload_once_changed_file_settles() {
+        let loaded = SystemTime::UNIX_EPOCH;
+        let changed = loaded + Duration::from_secs(1);
+
+        assert!(should_reload(Some(loaded), Some(changed), RELOAD_DEBOUNCE));
+        assert!(should_reload(None, Some(changed), RELOAD_DEBOUNCE));
+    }
+
+    #[test]
+    fn should_not_reload_unchanged_unsettled_or_missing_file() {
+        let loaded = SystemTime::UNIX_EPOCH;
+        let changed = loaded + Duration::from_secs(1);
+
+        assert!(!should_reload(Some(loaded), Some(loaded), RELOAD_DEBOUNCE));
+        assert!(!should_reload(Some(loaded), Some(changed), Duration::ZERO));
+        assert!(!should_reload(Some(loaded), None, RELOAD_DEBOUNCE));
+    }
+
+    #[test]
+    fn book_page_url_uses_normalised_isbn() {
+        let isbn = Isbn::from_str("978-0-19-953646-7").unwrap();
+
+        assert_eq!(
+            book_page_url(&isbn),
+            "https://openlibrary.org/isbn/9780199536467"
+        );
+    }
+
+    #[test]
+    fn open_book_page_without_isbn_sets_status_message() {
+        let mut tui = tui_with_books(&[("burmese days", Status::Want)]);
+
+        press(&mut tui, KeyCode::Char('o').into());
+
+        assert_eq!(
+            tui.status_message.as_deref(),
+            Some("No ISBN for burmese days, can't open its page")
+        );
+    }
+
+    #[test]
+    fn apply_filter_sets_status_message() {
+        let mut tui = tui_with_books(&[
+            ("burmese days", Status::Want),
+            ("animal farm", Status::Read),
+        ]);
+
+        press(&mut tui, KeyCode::Char('w').into());
+
+        assert_eq!(
+            tui.status_message.as_deref(),
+            Some("Showing 1 book(s) you want to read")
+        );
+    }
+
+    #[test]
+    fn status_message_clears_after_a_few_redraws() {
+        let mut tui = tui_with_books(&[("burmese days", Status::Want)]);
+        tui.set_status_message("Showing all books");
+
+        for _ in 0..=STATUS_MESSAGE_REDRAWS {
+            assert!(tui.status_message.is_some());
+            tui.tick_status_message();
+        }
+
+        assert_eq!(tui.status_message, None);
+    }
+
+    #[test]
+    fn clear_filter_reselects_previously_selected_book() {
+        let mut tui = tui_with_books(&[
+            ("burmese days", Status::Want),
+            ("animal farm", Status::Read),
+            ("coming up for air", Status::Want),
+        ]);
+        tui.move_cursor_down();
+        tui.move_cursor_down();
+        let selected = tui.filtered[tui.cursor];
+
+        tui.update(Message::ApplyFilter);
+        tui.update(Message::ClearFilter);
+
+        assert_eq!(tui.cursor, 2);
+        assert_eq!(tui.filtered[tui.cursor], selected);
+    }
+
+    #[test]
+    fn apply_filter_clamps_cursor_when_selected_book_is_hidden() {
+        let mut tui = tui_with_books(&[
+            ("burmese days", Status::Want),
+            ("animal farm", Status::Read),
+            ("homage to catalonia", Status::Read),
+        ]);
+        tui.move_cursor_down();
+        tui.move_cursor_down();
+
+        tui.update(Message::ApplyFilter);
+
+        assert_eq!(tui.cursor, 0);
+    }
 }
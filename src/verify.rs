@@ -0,0 +1,204 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    fs,
+    path::Path,
+    ptr,
+};
+
+use uuid::Uuid;
+
+use crate::{Book, Library, SpineError};
+
+/// What [`verify_file`] found in a library file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Report {
+    /// Number of books in the file, or 0 if it couldn't be read as a library.
+    pub books: usize,
+    pub issues: Vec<Issue>,
+}
+
+impl Report {
+    /// Returns whether no issues were found.
+    #[must_use]
+    pub const fn passed(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.passed() {
+            return writeln!(f, "PASS: checked {} book(s), no issues found.", self.books);
+        }
+        writeln!(
+            f,
+            "FAIL: found {} issue(s) in {} book(s):",
+            self.issues.len(),
+            self.books
+        )?;
+        for issue in &self.issues {
+            writeln!(f, "  - {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A problem with a library file. Books are numbered from 1 in the order they appear in it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Issue {
+    /// The file isn't valid JSON, or isn't shaped like a library.
+    InvalidJson(String),
+    MissingId {
+        book: usize,
+        title: String,
+    },
+    DuplicateId {
+        book: usize,
+        title: String,
+        id: Uuid,
+    },
+    /// The book failed [`crate::Book::validate`], e.g. an ISBN stored with hyphens, which ISBN
+    /// lookups wouldn't find.
+    InvalidBook {
+        book: usize,
+        title: String,
+        reason: String,
+    },
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidJson(reason) => write!(f, "Not a valid library file: {reason}"),
+            Self::MissingId { book, title } => write!(f, "Book {book} ('{title}') has no id."),
+            Self::DuplicateId { book, title, id } => write!(
+                f,
+                "Book {book} ('{title}') has the same id as an earlier book: {id}."
+            ),
+            Self::InvalidBook {
+                book,
+                title,
+                reason,
+            } => write!(f, "Book {book} ('{title}'): {reason}"),
+        }
+    }
+}
+
+/// Checks the library file at `path` is valid JSON and every book has a unique id and passes
+/// [`Library::validate_all`].
+///
+/// Unlike [`Library::open`], nothing is fixed up first, so problems `open` would quietly repair
+/// are reported too.
+///
+/// There is no index to check for consistency: the ISBN index isn't stored in the file but
+/// rebuilt from the books whenever it's opened. An ISBN stored in a form lookups wouldn't match
+/// is reported as an invalid book instead.
+///
+/// # Errors
+///
+/// Fails only if the file can't be read.
+pub fn verify_file(path: impl AsRef<Path>) -> Result<Report, SpineError> {
+    let contents = fs::read(path)?;
+    let library: Library = match serde_json::from_slice(&contents) {
+        Ok(library) => library,
+        Err(e) => {
+            return Ok(Report {
+                books: 0,
+                issues: vec![Issue::InvalidJson(e.to_string())],
+            });
+        }
+    };
+
+    let mut issues = id_issues(&library);
+    issues.extend(
+        library
+            .validate_all()
+            .into_iter()
+            .map(|(book, err)| Issue::InvalidBook {
+                book: position(&library, book),
+                title: book.title.clone(),
+                reason: err.to_string(),
+            }),
+    );
+
+    Ok(Report {
+        books: library.books.len(),
+        issues,
+    })
+}
+
+/// Finds books with a nil id, or an id an earlier book already has.
+fn id_issues(library: &Library) -> Vec<Issue> {
+    let mut seen = HashSet::new();
+    let mut issues = Vec::new();
+    for (i, book) in library.books.iter().enumerate() {
+        if book.id.is_nil() {
+            issues.push(Issue::MissingId {
+                book: i + 1,
+                title: book.title.clone(),
+            });
+        } else if !seen.insert(book.id) {
+            issues.push(Issue::DuplicateId {
+                book: i + 1,
+                title: book.title.clone(),
+                id: book.id,
+            });
+        }
+    }
+    issues
+}
+
+/// Returns the 1-based position of `book` in the library, found by address as its id may be
+/// missing or shared.
+fn position(library: &Library, book: &Book) -> usize {
+    library
+        .books
+        .iter()
+        .position(|b| ptr::eq(b, book))
+        .map_or(0, |i| i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn verify_file_passes_saved_library() {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("spine.json");
+        let mut library = Library::new();
+        library.add(Book {
+            title: "kim".into(),
+            author: "rudyard kipling".parse().unwrap(),
+            ..Default::default()
+        });
+        library.save(&path).unwrap();
+
+        let report = verify_file(&path).unwrap();
+
+        assert!(report.passed());
+        assert_eq!(report.books, 1);
+    }
+
+    #[test]
+    fn verify_file_reports_invalid_json() {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("spine.json");
+        fs::write(&path, r#"{"books": [}"#).unwrap();
+
+        let report = verify_file(&path).unwrap();
+
+        assert!(matches!(report.issues[..], [Issue::InvalidJson(_)]));
+    }
+
+    #[test]
+    fn verify_file_fails_for_absent_file() {
+        let tmp_dir = tempdir().unwrap();
+
+        let err = verify_file(tmp_dir.path().join("spine.json")).unwrap_err();
+
+        assert!(matches!(err, SpineError::Io(_)));
+    }
+}
@@ -1,7 +1,11 @@
-use std::{collections::HashSet, fs, path::Path, str::FromStr};
+#![allow(clippy::unwrap_used)]
+
+use std::{collections::HashSet, fs, path::Path, process::Command, str::FromStr};
 
 use assert_cmd::cargo::cargo_bin_cmd;
+use chrono::{Local, NaiveDate};
 use predicates::prelude::*;
+use tempfile::tempdir;
 use uuid::uuid;
 
 use spine::{Author, Book, Isbn, Library, Status};
@@ -51,9 +55,1245 @@ fn spine_add_adds_new_book_to_existing_library() {
         .append_context("main", "wrong output");
 
     let actual = Library::open(out_path).unwrap();
-    book2.id = actual.all().last().expect("book in library").id.clone();
+    let added = actual.all().last().expect("book in library");
+    book2.id = added.id;
+    book2.date_added = added.date_added;
     expected.add(book2);
-    assert_eq!(actual, expected);
+    assert_eq!(
+        actual.all().collect::<Vec<_>>(),
+        expected.all().collect::<Vec<_>>()
+    );
+    assert!(actual.updated_at() > actual.created_at());
 
     fs::remove_file(out_path).unwrap();
 }
+
+#[test]
+fn spine_add_with_no_save_leaves_library_file_untouched() {
+    let tmp_dir = tempdir().unwrap();
+    let lib_path = tmp_dir.path().join("spine.json");
+    fs::copy("tests/data/single_book.json", &lib_path).unwrap();
+    let before = fs::read(&lib_path).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args([
+            "--cli",
+            "add",
+            "--no-save",
+            "norwegian wood",
+            "haruki murakami",
+        ])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to add book")
+        .stdout(predicate::str::contains("Book added!"))
+        .append_context("main", "wrong output");
+    assert_eq!(fs::read(&lib_path).unwrap(), before);
+}
+
+#[test]
+fn spine_add_quiet_prints_nothing_but_saves() {
+    let tmp_dir = tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "add", "-q", "kim", "rudyard kipling"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to add quietly")
+        .stdout(predicate::str::is_empty())
+        .append_context("main", "printed to stdout");
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    assert_eq!(
+        actual.all().map(|b| b.title.as_str()).collect::<Vec<_>>(),
+        vec!["kim"]
+    );
+}
+
+#[test]
+fn spine_batch_applies_each_line_and_saves_once() {
+    let tmp_dir = tempdir().unwrap();
+    let batch_path = tmp_dir.path().join("batch.txt");
+    fs::write(
+        &batch_path,
+        concat!(
+            "add \"norwegian wood\" \"haruki murakami\" --reading\n",
+            "add 'kim' 'rudyard kipling' --read --tag classic\n",
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "batch", "batch.txt"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to run batch")
+        .stdout(predicate::str::contains("2 succeeded, 0 failed"))
+        .append_context("main", "wrong output");
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    let titles: Vec<_> = actual.all().map(|b| b.title.as_str()).collect();
+    assert_eq!(titles, vec!["norwegian wood", "kim"]);
+    assert_eq!(actual.all().last().unwrap().status, Status::Read);
+}
+
+#[test]
+fn spine_batch_exits_with_3_when_a_line_fails() {
+    let tmp_dir = tempdir().unwrap();
+    fs::write(
+        tmp_dir.path().join("batch.txt"),
+        "add 'kim' 'rudyard kipling\nadd 'emma' 'jane austen'\n",
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "batch", "batch.txt"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .code(3)
+        .append_context("main", "wrong exit code")
+        .stderr(predicate::str::contains("1 of 2 batch line(s) failed."))
+        .append_context("main", "wrong error");
+}
+
+#[test]
+fn spine_batch_rejects_global_options_on_a_line() {
+    let tmp_dir = tempdir().unwrap();
+    fs::write(
+        tmp_dir.path().join("batch.txt"),
+        "add 'kim' 'rudyard kipling' --no-save\nadd 'emma' 'jane austen'\n",
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "batch", "batch.txt"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .code(3)
+        .append_context("main", "wrong exit code")
+        .stderr(predicate::str::contains(
+            "line 1: --no-save applies to the whole batch",
+        ))
+        .append_context("main", "wrong error");
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    let titles: Vec<_> = actual.all().map(|b| b.title.as_str()).collect();
+    assert_eq!(titles, vec!["emma"]);
+}
+
+#[test]
+fn spine_import_titles_adds_want_book_per_line() {
+    let tmp_dir = tempdir().unwrap();
+    fs::write(
+        tmp_dir.path().join("titles.txt"),
+        "the remains of the day\n\n  kim | rudyard kipling\n",
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "import", "--titles", "titles.txt"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to import titles")
+        .stdout(predicate::str::contains(
+            "2 book(s) added, 1 blank line(s) skipped.",
+        ))
+        .append_context("main", "wrong output");
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    let books: Vec<_> = actual
+        .all()
+        .map(|b| (b.title.as_str(), b.author.to_string(), b.status))
+        .collect();
+    assert_eq!(
+        books,
+        vec![
+            ("the remains of the day", String::new(), Status::Want),
+            ("kim", "rudyard kipling".to_owned(), Status::Want),
+        ]
+    );
+}
+
+#[test]
+fn spine_import_csv_reads_back_csv_export() {
+    let tmp_dir = tempdir().unwrap();
+    let mut library = Library::new();
+    library.add(Book {
+        title: "kim".to_owned(),
+        author: Author::from_str("rudyard kipling").unwrap(),
+        isbn: Some(Isbn::from_str("9780199536467").unwrap()),
+        status: Status::Read,
+        tags: HashSet::from(["classic".into(), "india".into()]),
+        date_added: NaiveDate::from_ymd_opt(2024, 3, 1),
+        date_finished: NaiveDate::from_ymd_opt(2024, 3, 20),
+        times_read: 1,
+        ..Default::default()
+    });
+    library.save(tmp_dir.path().join("spine.json")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args([
+        "--cli",
+        "export",
+        "--format",
+        "csv",
+        "--output",
+        "books.csv",
+    ])
+    .current_dir(tmp_dir.path())
+    .assert()
+    .success()
+    .append_context("main", "failed to export");
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "import", "--csv", "books.csv", "--profile", "copy"])
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 book(s) added!"))
+        .append_context("main", "failed to import export");
+
+    let imported = Library::open(tmp_dir.path().join("copy.json")).unwrap();
+    let book = imported.all().next().unwrap();
+    let original = library.all().next().unwrap();
+    assert_eq!(
+        *book,
+        Book {
+            id: book.id,
+            ..original.clone()
+        }
+    );
+}
+
+#[test]
+fn spine_remove_exits_with_1_when_no_book_found() {
+    let tmp_dir = tempdir().unwrap();
+    fs::copy(
+        "tests/data/single_book.json",
+        tmp_dir.path().join("spine.json"),
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "remove", "--title", "war and peace"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .code(1)
+        .append_context("main", "wrong exit code")
+        .stderr(predicate::str::contains("No books found."))
+        .append_context("main", "wrong output");
+}
+
+#[test]
+fn spine_show_exits_with_2_when_library_cannot_be_read() {
+    let tmp_dir = tempdir().unwrap();
+    fs::create_dir(tmp_dir.path().join("spine.json")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "show"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert.code(2).append_context("main", "wrong exit code");
+}
+
+#[test]
+fn spine_add_with_log_appends_audit_line() {
+    let tmp_dir = tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args([
+            "--cli",
+            "add",
+            "--log",
+            "spine.log",
+            "norwegian wood",
+            "haruki murakami",
+        ])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to add book");
+    let log = fs::read_to_string(tmp_dir.path().join("spine.log")).unwrap();
+    let lines: Vec<_> = log.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let entry: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(entry["operation"], "add");
+    assert_eq!(entry["title"], "norwegian wood");
+    assert!(entry["timestamp"].is_string());
+    assert_ne!(entry["id"], "00000000-0000-0000-0000-000000000000");
+}
+
+#[test]
+fn spine_add_with_existing_cover_stores_path() {
+    let tmp_dir = tempdir().unwrap();
+    let cover_path = tmp_dir.path().join("kim.jpg");
+    fs::write(&cover_path, b"not really a jpeg").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args([
+            "--cli",
+            "add",
+            "--cover",
+            "kim.jpg",
+            "kim",
+            "rudyard kipling",
+        ])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to add book")
+        .stderr(predicate::str::is_empty())
+        .append_context("main", "unexpected warning");
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    let book = actual.all().next().unwrap();
+    assert_eq!(book.cover_path.as_deref(), Some(Path::new("kim.jpg")));
+}
+
+#[test]
+fn spine_add_with_missing_cover_warns_but_saves() {
+    let tmp_dir = tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args([
+            "--cli",
+            "add",
+            "--cover",
+            "kim.jpg",
+            "kim",
+            "rudyard kipling",
+        ])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to add book")
+        .stderr(predicate::str::contains(
+            "Warning: cover image kim.jpg does not exist",
+        ))
+        .append_context("main", "missing warning");
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    let book = actual.all().next().unwrap();
+    assert_eq!(book.cover_path.as_deref(), Some(Path::new("kim.jpg")));
+}
+
+#[test]
+fn spine_add_without_status_flag_uses_default_status_from_env() {
+    let tmp_dir = tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "add", "kim", "rudyard kipling"])
+        .env("SPINE_DEFAULT_STATUS", "read")
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to add book");
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    let added = actual.all().next().unwrap();
+    assert_eq!(added.status, Status::Read);
+    assert_eq!(added.times_read, 1);
+    assert_eq!(added.date_finished, Some(Local::now().date_naive()));
+}
+
+#[test]
+fn spine_add_takes_defaults_from_config_unless_flag_given() {
+    let tmp_dir = tempdir().unwrap();
+    let config_dir = tmp_dir.path().join("config");
+    fs::create_dir_all(config_dir.join("spine")).unwrap();
+    fs::write(
+        config_dir.join("spine/spine.toml"),
+        "default_status = \"read\"\ndata_dir = \"books\"\ncolour = \"never\"\n",
+    )
+    .unwrap();
+    fs::create_dir(tmp_dir.path().join("books")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "add", "kim", "rudyard kipling"])
+        .env("XDG_CONFIG_HOME", &config_dir)
+        .env_remove("SPINE_DEFAULT_STATUS")
+        .env_remove("SPINE_DATA_DIR")
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("unknown setting 'colour'"))
+        .append_context("main", "didn't warn about unknown setting");
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "add", "--reading", "emma", "jane austen"])
+        .env("XDG_CONFIG_HOME", &config_dir)
+        .env_remove("SPINE_DEFAULT_STATUS")
+        .env_remove("SPINE_DATA_DIR")
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .append_context("main", "failed to add book with status flag");
+
+    let actual = Library::open(tmp_dir.path().join("books/spine.json")).unwrap();
+    let statuses: Vec<_> = actual.all().map(|b| b.status).collect();
+    assert_eq!(statuses, [Status::Read, Status::Reading]);
+}
+
+#[test]
+fn spine_add_duplicates_from_config_file_is_overridden_by_flag() {
+    let tmp_dir = tempdir().unwrap();
+    let config_path = tmp_dir.path().join("my_spine.toml");
+    fs::write(&config_path, "duplicates = \"reject\"\n").unwrap();
+    let add = |extra: &[&str]| {
+        let mut cmd = cargo_bin_cmd!("spine");
+        cmd.args(["--cli", "--config"])
+            .arg(&config_path)
+            .args(["add", "kim", "Rudyard Kipling"])
+            .args(extra)
+            .current_dir(tmp_dir.path())
+            .assert()
+    };
+
+    add(&[]).success();
+    add(&[])
+        .code(3)
+        .stderr(predicate::str::contains("already in your library"))
+        .append_context("main", "config didn't reject duplicate");
+    add(&["--duplicates", "warn"])
+        .success()
+        .stderr(predicate::str::contains("adding it again"))
+        .append_context("main", "flag didn't override config");
+
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    assert_eq!(actual.all().count(), 2);
+}
+
+#[test]
+fn spine_add_with_uuid_seed_gives_same_ids_every_run() {
+    let run = || {
+        let tmp_dir = tempdir().unwrap();
+        for title in ["kim", "burmese days"] {
+            cargo_bin_cmd!("spine")
+                .args(["--cli", "add", title, "george orwell"])
+                .env("SPINE_UUID_SEED", "42")
+                .current_dir(tmp_dir.path())
+                .assert()
+                .success()
+                .append_context("main", "failed to add book");
+        }
+        let library = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+        library.all().map(|b| b.id).collect::<Vec<_>>()
+    };
+
+    let first = run();
+
+    assert_eq!(first, run());
+    assert_ne!(first[0], first[1]);
+}
+
+#[test]
+fn spine_locks_library_only_while_changing_it() {
+    let tmp_dir = tempdir().unwrap();
+    let lock_path = tmp_dir.path().join("spine.json.lock");
+    fs::copy(
+        "tests/data/single_book.json",
+        tmp_dir.path().join("spine.json"),
+    )
+    .unwrap();
+    fs::create_dir(&lock_path).unwrap();
+
+    cargo_bin_cmd!("spine")
+        .args(["--cli", "show", "--all"])
+        .current_dir(&tmp_dir)
+        .assert()
+        .success()
+        .append_context("main", "show should not need the lock");
+    cargo_bin_cmd!("spine")
+        .args(["--cli", "add", "kim", "rudyard kipling"])
+        .current_dir(&tmp_dir)
+        .assert()
+        .failure()
+        .append_context("main", "add should need the lock");
+
+    fs::remove_dir(&lock_path).unwrap();
+    cargo_bin_cmd!("spine")
+        .args(["--cli", "add", "kim", "rudyard kipling"])
+        .current_dir(&tmp_dir)
+        .assert()
+        .success();
+    assert!(!lock_path.exists(), "lock file left behind");
+}
+
+#[test]
+fn spine_concurrent_adds_keep_every_book() {
+    let tmp_dir = tempdir().unwrap();
+
+    let children: Vec<_> = (0..8)
+        .map(|i| {
+            Command::new(assert_cmd::cargo::cargo_bin!("spine"))
+                .args(["--cli", "add", &format!("book {i}"), "george orwell"])
+                .current_dir(tmp_dir.path())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    assert_eq!(actual.all().count(), 8);
+}
+
+#[test]
+fn spine_update_status_all_updates_every_book_with_tag() {
+    let tmp_dir = tempdir().unwrap();
+    let mut library = Library::new();
+    for (title, tag) in [
+        ("dune", "sci-fi"),
+        ("hyperion", "sci-fi"),
+        ("emma", "classic"),
+    ] {
+        library.add(Book {
+            title: title.to_owned(),
+            author: Author::from_str("some author").unwrap(),
+            tags: HashSet::from([tag.to_owned()]),
+            ..Default::default()
+        });
+    }
+    library.save(tmp_dir.path().join("spine.json")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args([
+            "--cli",
+            "update",
+            "status",
+            "--reading",
+            "--tag",
+            "sci-fi",
+            "--all",
+        ])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to update books")
+        .stdout(predicate::str::contains("2 book(s)'s status updated"))
+        .append_context("main", "wrong output");
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    let statuses: Vec<_> = actual.all().map(|b| b.status).collect();
+    assert_eq!(statuses, [Status::Reading, Status::Reading, Status::Want]);
+}
+
+#[test]
+fn spine_notes_are_set_by_add_and_update_but_query_only_searches() {
+    let tmp_dir = tempdir().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args([
+        "--cli",
+        "add",
+        "kim",
+        "rudyard kipling",
+        "--notes",
+        "from sam",
+    ])
+    .current_dir(tmp_dir.path())
+    .assert()
+    .success()
+    .append_context("main", "failed to add book with notes");
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args([
+        "--cli",
+        "update",
+        "notes",
+        "--notes",
+        "lent to priya",
+        "--title",
+        "kim",
+    ])
+    .current_dir(tmp_dir.path())
+    .assert()
+    .success()
+    .append_context("main", "failed to update notes");
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "search", "priya"])
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("kim"))
+        .append_context("main", "query didn't match notes");
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "remove", "priya"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .append_context("main", "remove accepted a query");
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    let notes: Vec<_> = actual.all().map(|b| b.notes.as_deref()).collect();
+    assert_eq!(notes, [Some("lent to priya")]);
+}
+
+#[test]
+fn spine_remove_first_removes_earliest_added_match() {
+    let tmp_dir = tempdir().unwrap();
+    let mut library = Library::new();
+    for title in ["burmese days", "animal farm", "coming up for air"] {
+        library.add(Book {
+            title: title.to_owned(),
+            author: Author::from_str("george orwell").unwrap(),
+            ..Default::default()
+        });
+    }
+    library.save(tmp_dir.path().join("spine.json")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "remove", "--author", "orwell", "--first"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to remove book");
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    let titles: Vec<_> = actual.all().map(|b| b.title.as_str()).collect();
+    assert_eq!(titles, ["animal farm", "coming up for air"]);
+}
+
+#[test]
+fn spine_clone_adds_unread_copy_of_matching_book() {
+    let tmp_dir = tempdir().unwrap();
+    let original = Book {
+        title: "dune".to_owned(),
+        author: Author::from_str("frank herbert").unwrap(),
+        status: Status::Read,
+        tags: HashSet::from(["sci-fi".into()]),
+        ..Default::default()
+    };
+    let mut library = Library::new();
+    library.add(original);
+    library.save(tmp_dir.path().join("spine.json")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "clone", "--title", "dune"])
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Book cloned!"))
+        .append_context("main", "failed to clone book");
+
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    let [original, copy] = actual.all().collect::<Vec<_>>()[..] else {
+        panic!("expected the original and its copy");
+    };
+    assert_ne!(copy.id, original.id);
+    assert_eq!(
+        (&copy.title, &copy.author),
+        (&original.title, &original.author)
+    );
+    assert_eq!(copy.tags, original.tags);
+    assert_eq!(copy.status, Status::Want);
+    assert_eq!(original.status, Status::Read);
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "clone", "--title", "dune"])
+        .current_dir(tmp_dir.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Found 2 matching books"))
+        .append_context("main", "cloned one of several matches");
+}
+
+#[test]
+fn spine_report_shows_this_weeks_books_by_section() {
+    let tmp_dir = tempdir().unwrap();
+    let today = Local::now().date_naive();
+    let long_ago = NaiveDate::from_ymd_opt(2001, 1, 1).unwrap();
+    let mut library = Library::new();
+    for (title, status, added, started, finished) in [
+        ("kim", Status::Read, long_ago, Some(long_ago), Some(today)),
+        ("dune", Status::Reading, long_ago, Some(today), None),
+        ("emma", Status::Want, today, None, None),
+        (
+            "ulysses",
+            Status::Read,
+            long_ago,
+            Some(long_ago),
+            Some(long_ago),
+        ),
+    ] {
+        library.add(Book {
+            title: title.to_owned(),
+            author: Author::from_str("someone").unwrap(),
+            status,
+            date_added: Some(added),
+            date_started: started,
+            date_finished: finished,
+            ..Default::default()
+        });
+    }
+    library.save(tmp_dir.path().join("spine.json")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "report", "--period", "week"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .stdout(predicate::str::contains(
+            "\nFinished (1)\n  kim, someone\n\nStarted (1)\n  dune, someone\n\nAdded (1)\n  emma, someone\n",
+        ))
+        .stdout(predicate::str::contains("ulysses").not())
+        .append_context("main", "wrong report");
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn spine_search_regex_matches_titles_and_rejects_invalid_pattern() {
+    let tmp_dir = tempdir().unwrap();
+    let mut library = Library::new();
+    for title in ["The Road 1937", "the hobbit 1937", "The Trial"] {
+        library.add(Book {
+            title: title.to_owned(),
+            author: Author::from_str("someone").unwrap(),
+            ..Default::default()
+        });
+    }
+    library.save(tmp_dir.path().join("spine.json")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "search", "--regex", r"^the .*\d{4}$"])
+        .env("NO_COLOR", "1")
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "The Road 1937, someone\nthe hobbit 1937, someone\n",
+        ))
+        .stdout(predicate::str::contains("The Trial").not())
+        .append_context("main", "wrong regex matches");
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "search", "--regex", "the (road"])
+        .current_dir(tmp_dir.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Invalid regex 'the (road'"))
+        .append_context("main", "invalid regex not rejected");
+}
+
+#[test]
+fn spine_share_snippet_adds_same_book_to_another_library() {
+    let ours = tempdir().unwrap();
+    let theirs = tempdir().unwrap();
+    let mut library = Library::new();
+    library.add(Book {
+        title: "kim".to_owned(),
+        author: Author::from_str("rudyard kipling").unwrap(),
+        isbn: Some(Isbn::from_str("9780199536467").unwrap()),
+        status: Status::Reading,
+        ..Default::default()
+    });
+    library.save(ours.path().join("spine.json")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let output = cmd
+        .args(["--cli", "share", "--title", "kim"])
+        .current_dir(ours.path())
+        .output()
+        .unwrap();
+    let snippet = String::from_utf8(output.stdout).unwrap();
+    assert!(snippet.starts_with("spine://book/"), "{snippet}");
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "add", "--from", snippet.trim()])
+        .current_dir(theirs.path())
+        .assert()
+        .success()
+        .append_context("main", "failed to add shared book");
+
+    let original = Library::open(ours.path().join("spine.json")).unwrap();
+    let original = original.all().next().unwrap();
+    let shared = Library::open(theirs.path().join("spine.json")).unwrap();
+    let [added] = shared.all().collect::<Vec<_>>()[..] else {
+        panic!("expected one book");
+    };
+    assert_ne!(added.id, original.id);
+    assert_eq!(
+        (&added.title, &added.author, &added.isbn, added.status),
+        (
+            &original.title,
+            &original.author,
+            &original.isbn,
+            original.status
+        )
+    );
+}
+
+#[test]
+fn spine_show_group_orders_books_within_each_status() {
+    let tmp_dir = tempdir().unwrap();
+    let day = |d| NaiveDate::from_ymd_opt(2024, 3, d);
+    let mut library = Library::new();
+    for (title, status, priority, started, finished) in [
+        ("kim", Status::Read, None, None, day(1)),
+        ("emma", Status::Want, Some(3), None, None),
+        ("dune", Status::Reading, None, day(9), None),
+        ("ulysses", Status::Read, None, None, day(20)),
+        ("walden", Status::Want, Some(1), None, None),
+        ("beloved", Status::Reading, None, day(2), None),
+    ] {
+        library.add(Book {
+            title: title.to_owned(),
+            author: Author::from_str("someone").unwrap(),
+            status,
+            priority,
+            date_started: started,
+            date_finished: finished,
+            ..Default::default()
+        });
+    }
+    library.save(tmp_dir.path().join("spine.json")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "show", "--group"])
+        .env_remove("SPINE_GROUP_SORT")
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(concat!(
+            "Reading:\n\nbeloved, someone\ndune, someone\n\n",
+            "Want to read:\n\nwalden, someone\nemma, someone\n\n",
+            "Read:\n\nulysses, someone\nkim, someone\n",
+        )))
+        .append_context("main", "wrong default order within groups");
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args([
+        "--cli",
+        "show",
+        "--group",
+        "--group-sort",
+        "read=title,want=priority-desc",
+    ])
+    .current_dir(tmp_dir.path())
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(concat!(
+        "Want to read:\n\nemma, someone\nwalden, someone\n\n",
+        "Read:\n\nkim, someone\nulysses, someone\n",
+    )))
+    .append_context("main", "wrong configured order within groups");
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "show", "--group", "--title", "kim"])
+        .env_remove("SPINE_GROUP_SORT")
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::diff("Read:\n\nkim, someone\n\n"))
+        .append_context("main", "--group should only list matching books");
+}
+
+#[test]
+fn spine_peek_prints_highest_priority_then_oldest_want_book() {
+    let tmp_dir = tempdir().unwrap();
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "peek"])
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to read next"))
+        .append_context("main", "wrong output for empty library");
+
+    let day = |d| NaiveDate::from_ymd_opt(2024, 3, d);
+    let mut library = Library::new();
+    for (title, status, priority, added) in [
+        ("kim", Status::Want, None, day(1)),
+        ("emma", Status::Want, Some(2), day(9)),
+        ("dune", Status::Read, Some(1), day(1)),
+        ("ulysses", Status::Want, Some(2), day(5)),
+    ] {
+        library.add(Book {
+            title: title.to_owned(),
+            author: Author::from_str("someone").unwrap(),
+            status,
+            priority,
+            date_added: added,
+            ..Default::default()
+        });
+    }
+    library.save(tmp_dir.path().join("spine.json")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "peek"])
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .stdout("ulysses, someone\n")
+        .append_context("main", "wrong book picked");
+}
+
+#[test]
+fn spine_tag_adds_tag_to_all_matches_then_removes_it_from_one() {
+    let tmp_dir = tempdir().unwrap();
+    let mut library = Library::new();
+    for (title, author) in [
+        ("burmese days", "george orwell"),
+        ("animal farm", "george orwell"),
+        ("kim", "rudyard kipling"),
+    ] {
+        library.add(Book {
+            title: title.to_owned(),
+            author: Author::from_str(author).unwrap(),
+            ..Default::default()
+        });
+    }
+    library.save(tmp_dir.path().join("spine.json")).unwrap();
+    let tagged_titles = || {
+        let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+        actual
+            .all()
+            .filter(|b| b.tags.contains("summer-reading"))
+            .map(|b| b.title.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args([
+        "--cli",
+        "tag",
+        "add",
+        " Summer-Reading ",
+        "--author",
+        "orwell",
+        "--all",
+    ])
+    .current_dir(tmp_dir.path())
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Tagged 2 book(s)"))
+    .append_context("main", "failed to add tag");
+    assert_eq!(tagged_titles(), ["burmese days", "animal farm"]);
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args([
+        "--cli",
+        "tag",
+        "remove",
+        "summer-reading",
+        "--title",
+        "animal farm",
+    ])
+    .current_dir(tmp_dir.path())
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(
+        "Removed 'summer-reading' from 1 book(s)",
+    ))
+    .append_context("main", "failed to remove tag");
+    assert_eq!(tagged_titles(), ["burmese days"]);
+}
+
+#[test]
+fn spine_verify_reports_issues_in_broken_library() {
+    let tmp_dir = tempdir().unwrap();
+    let path = tmp_dir.path().join("spine.json");
+    fs::copy("tests/data/broken_library.json", &path).unwrap();
+    let before = fs::read_to_string(&path).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "verify"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .code(3)
+        .stdout(predicate::str::contains(
+            "FAIL: found 5 issue(s) in 4 book(s)",
+        ))
+        .stdout(predicate::str::contains(
+            "Book 2 ('norwegian wood') has the same id as an earlier book",
+        ))
+        .stdout(predicate::str::contains("Book 3 (' ') has no id."))
+        .stdout(predicate::str::contains(
+            "Book 1 ('hadji murat'): Invalid ISBN",
+        ))
+        .stdout(predicate::str::contains(
+            "Book 3 (' '): Invalid title: must not be empty.",
+        ))
+        .stdout(predicate::str::contains(
+            "Book 4 ('the idiot'): Invalid rating",
+        ))
+        .append_context("main", "verify didn't report the broken books");
+    assert_eq!(fs::read_to_string(&path).unwrap(), before);
+}
+
+#[test]
+fn spine_verify_passes_valid_library() {
+    let tmp_dir = tempdir().unwrap();
+    fs::copy(
+        "tests/data/multi_book.json",
+        tmp_dir.path().join("spine.json"),
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "verify"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .stdout(predicate::str::starts_with("PASS: checked"))
+        .append_context("main", "verify failed a valid library");
+}
+
+#[test]
+fn spine_search_count_prints_only_number_of_matches() {
+    let tmp_dir = tempdir().unwrap();
+    let mut library = Library::new();
+    for (title, author, status) in [
+        ("burmese days", "george orwell", Status::Read),
+        ("middlemarch", "george eliot", Status::Want),
+        ("animal farm", "george orwell", Status::Want),
+        ("kim", "rudyard kipling", Status::Want),
+    ] {
+        library.add(Book {
+            title: title.to_owned(),
+            author: Author::from_str(author).unwrap(),
+            status,
+            ..Default::default()
+        });
+    }
+    library.save(tmp_dir.path().join("spine.json")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "search", "--author", "george", "--count"])
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .stdout("3\n")
+        .append_context("main", "wrong count for search");
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args([
+        "--cli", "show", "--author", "george", "--status", "want", "--count",
+    ])
+    .current_dir(tmp_dir.path())
+    .assert()
+    .success()
+    .stdout("2\n")
+    .append_context("main", "wrong count for show with a status filter");
+}
+
+#[test]
+fn spine_show_lists_cjk_and_combining_titles_intact() {
+    let tmp_dir = tempdir().unwrap();
+    for (title, author) in [
+        ("ノルウェイの森", "村上 春樹"),
+        ("Cafe\u{301} society", "zoë heller"),
+    ] {
+        let mut cmd = cargo_bin_cmd!("spine");
+        cmd.args(["--cli", "add", title, author])
+            .current_dir(tmp_dir.path())
+            .assert()
+            .success()
+            .append_context("main", "failed to add book");
+    }
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "show"])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .stdout(predicate::str::contains("ノルウェイの森, 村上 春樹\n"))
+        .stdout(predicate::str::contains(
+            "Cafe\u{301} society, zoë heller\n",
+        ))
+        .append_context("main", "titles garbled");
+}
+
+#[cfg(unix)]
+#[test]
+fn spine_handles_non_utf8_arguments_without_panicking() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+    let tmp_dir = tempdir().unwrap();
+    // "テ" in Shift-JIS.
+    let shift_jis = OsStr::from_bytes(b"\x83\x65");
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.arg("--cli")
+        .arg("--profile")
+        .arg(shift_jis)
+        .args(["add", "kim", "rudyard kipling"])
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .append_context("main", "failed to use non-UTF-8 profile");
+    let mut file_name = shift_jis.to_owned();
+    file_name.push(".json");
+    assert!(tmp_dir.path().join(file_name).exists());
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "add"])
+        .arg(shift_jis)
+        .arg("author")
+        .current_dir(tmp_dir.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("invalid UTF-8"))
+        .append_context("main", "non-UTF-8 title didn't fail cleanly");
+}
+
+#[test]
+fn spine_add_stdin_adds_piped_book() {
+    let tmp_dir = tempdir().unwrap();
+    let book = r#"{
+        "title": "kim",
+        "author": {"first_name": "rudyard", "surname": "kipling"},
+        "isbn": {"Isbn13": "9780199536467"},
+        "status": "read"
+    }"#;
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "add", "--stdin"])
+        .write_stdin(book)
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to add book")
+        .stdout(predicate::str::contains("1 book(s) added!"))
+        .append_context("main", "wrong output");
+    let actual = Library::open(tmp_dir.path().join("spine.json")).unwrap();
+    let added = actual.all().next().unwrap();
+    assert_eq!(added.title, "kim");
+    assert_eq!(added.status, Status::Read);
+    assert!(!added.id.is_nil());
+}
+
+#[test]
+fn spine_add_stdin_adds_nothing_if_any_book_invalid() {
+    let tmp_dir = tempdir().unwrap();
+    let books = r#"[
+        {"title": "kim", "author": {"first_name": "rudyard", "surname": "kipling"}, "status": "read"},
+        {"title": " ", "author": {"first_name": "george", "surname": "orwell"}, "status": "want"}
+    ]"#;
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args(["--cli", "add", "--stdin"])
+        .write_stdin(books)
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .code(3)
+        .append_context("main", "invalid book was accepted")
+        .stderr(predicate::str::contains("book 2: Invalid title"))
+        .append_context("main", "wrong error");
+    assert!(!tmp_dir.path().join("spine.json").exists());
+}
+
+#[test]
+fn spine_add_stdin_rejects_flags_for_book_details() {
+    let tmp_dir = tempdir().unwrap();
+
+    for flags in [
+        &["--read"][..],
+        &["--isbn", "9780199536467"],
+        &["--tag", "classic"],
+    ] {
+        let mut cmd = cargo_bin_cmd!("spine");
+        cmd.args(["--cli", "add", "--stdin"])
+            .args(flags)
+            .write_stdin("{}")
+            .current_dir(tmp_dir.path())
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("cannot be used with"))
+            .append_context("main", "flag ignored by --stdin was accepted");
+    }
+    assert!(!tmp_dir.path().join("spine.json").exists());
+}
+
+#[test]
+fn spine_add_with_profile_uses_separate_library() {
+    let tmp_dir = tempdir().unwrap();
+    let default_path = tmp_dir.path().join("spine.json");
+    fs::copy("tests/data/single_book.json", &default_path).unwrap();
+    let before = fs::read(&default_path).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("spine");
+    let assert = cmd
+        .args([
+            "--cli",
+            "add",
+            "--profile",
+            "work",
+            "kim",
+            "rudyard kipling",
+        ])
+        .current_dir(tmp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .append_context("main", "failed to add book");
+    let work = Library::open(tmp_dir.path().join("work.json")).unwrap();
+    assert_eq!(
+        work.all().map(|b| b.title.as_str()).collect::<Vec<_>>(),
+        ["kim"]
+    );
+    assert_eq!(fs::read(&default_path).unwrap(), before);
+
+    fs::write(tmp_dir.path().join("export.json"), "[]").unwrap();
+    let mut cmd = cargo_bin_cmd!("spine");
+    cmd.args(["--cli", "profiles", "--profile", "work"])
+        .current_dir(tmp_dir.path())
+        .assert()
+        .success()
+        .stdout("  spine\n* work\n");
+}